@@ -0,0 +1,124 @@
+/*
+    The lookups are measured against the HashMap layout PiecePlacement had before it moved to
+    arrays and a mailbox, kept below as a baseline. Move generation and perft only run on the
+    engine's own layout; their HashMap numbers come from checking out the parent of the commit
+    "Store the piece placement in arrays with cached occupancy and a mailbox", copying in this
+    file without the baseline benches plus the criterion and [[bench]] entries of Cargo.toml,
+    and running `cargo bench --bench piece_placement` there and on the commit itself
+*/
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use jaingo_unchaind_rust::{
+    bitboard::PiecePlacement, fen_parser::parse_fen, move_generator::generate_all_moves,
+    moves::Square, perft::perft, piece::PieceType, position::Color,
+};
+
+static KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+struct HashMapPiecePlacement(HashMap<Color, HashMap<PieceType, u64>>);
+
+impl HashMapPiecePlacement {
+    fn new(piece_placement: &PiecePlacement) -> HashMapPiecePlacement {
+        let mut colors = HashMap::new();
+        for color in Color::iterator() {
+            let mut pieces = HashMap::new();
+            for piece in PieceType::iterator() {
+                pieces.insert(*piece, piece_placement.pieces(color, piece).0);
+            }
+            colors.insert(*color, pieces);
+        }
+        return HashMapPiecePlacement(colors);
+    }
+
+    fn pieces(&self, color: &Color, piece: &PieceType) -> u64 {
+        return *self.0.get(color).unwrap().get(piece).unwrap();
+    }
+
+    fn occupancy(&self, color: &Color) -> u64 {
+        return self
+            .0
+            .get(color)
+            .unwrap()
+            .values()
+            .fold(0, |occupancy, pieces| occupancy | pieces);
+    }
+
+    fn all_occupancy(&self) -> u64 {
+        return self.occupancy(&Color::White) | self.occupancy(&Color::Black);
+    }
+
+    fn piece_on(&self, square: &Square) -> Option<(Color, PieceType)> {
+        for color in Color::iterator() {
+            for piece in PieceType::iterator() {
+                if self.pieces(color, piece) & (1 << square.0) != 0 {
+                    return Some((*color, *piece));
+                }
+            }
+        }
+        return None;
+    }
+}
+
+fn piece_lookups(c: &mut Criterion) {
+    let position = parse_fen(KIWIPETE).unwrap();
+    let piece_placement = &position.piece_placement;
+    let baseline = HashMapPiecePlacement::new(piece_placement);
+
+    let mut group = c.benchmark_group("piece_on every square");
+    group.bench_function("arrays and mailbox", |b| {
+        b.iter(|| {
+            for square in 0..64 {
+                black_box(piece_placement.piece_on(&Square(square)));
+            }
+        })
+    });
+    group.bench_function("hashmap baseline", |b| {
+        b.iter(|| {
+            for square in 0..64 {
+                black_box(baseline.piece_on(&Square(square)));
+            }
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("all_occupancy");
+    group.bench_function("arrays and mailbox", |b| {
+        b.iter(|| black_box(piece_placement).all_occupancy())
+    });
+    group.bench_function("hashmap baseline", |b| {
+        b.iter(|| black_box(&baseline).all_occupancy())
+    });
+    group.finish();
+}
+
+fn move_generation(c: &mut Criterion) {
+    let position = parse_fen(KIWIPETE).unwrap();
+    c.bench_function("generate_all_moves kiwipete", |b| {
+        b.iter(|| generate_all_moves(black_box(&position)))
+    });
+
+    let mut position = parse_fen(KIWIPETE).unwrap();
+    c.bench_function("make and unmake kiwipete moves", |b| {
+        let moves = generate_all_moves(&position);
+        b.iter(|| {
+            for mv in &moves {
+                let undo = position.make_move(*mv);
+                position.unmake_move(*mv, undo);
+            }
+        })
+    });
+}
+
+fn perft_kiwipete(c: &mut Criterion) {
+    let mut position = parse_fen(KIWIPETE).unwrap();
+    let mut group = c.benchmark_group("perft");
+    group.sample_size(10);
+    group.bench_function("kiwipete depth 3", |b| {
+        b.iter(|| perft(&mut position, black_box(3)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, piece_lookups, move_generation, perft_kiwipete);
+criterion_main!(benches);
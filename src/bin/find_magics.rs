@@ -0,0 +1,91 @@
+/*
+    Searches magic numbers for the rook and bishop attack tables in src/magic.rs and prints
+    them as Rust constants
+
+        cargo run --release --bin find_magics [seed]
+*/
+use std::env;
+
+use jaingo_unchaind_rust::{
+    bitboard::Bitboard,
+    magic::{
+        occupancy_subsets, relevant_occupancy, sliding_attacks, BISHOP_DIRECTIONS, ROOK_DIRECTIONS,
+    },
+    moves::Square,
+};
+
+// xorshift64
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        return self.0;
+    }
+
+    // magic numbers with few set bits are found a lot faster
+    fn sparse(&mut self) -> u64 {
+        return self.next() & self.next() & self.next();
+    }
+}
+
+fn find_magic(square: &Square, directions: &[(i8, i8)], random: &mut Random) -> u64 {
+    let mask = relevant_occupancy(square, directions);
+    let shift = 64 - mask.count();
+    let subsets = occupancy_subsets(&mask);
+    let attacks: Vec<Bitboard> = subsets
+        .iter()
+        .map(|subset| sliding_attacks(square, subset, directions))
+        .collect();
+
+    let mut table: Vec<Option<Bitboard>> = vec![None; subsets.len()];
+    loop {
+        let magic = random.sparse();
+        // the high byte of the product has to spread well to be usable
+        if (mask.0.wrapping_mul(magic) & 0xff00000000000000).count_ones() < 6 {
+            continue;
+        }
+
+        table.iter_mut().for_each(|entry| *entry = None);
+        let collision_free = subsets.iter().zip(&attacks).all(|(subset, attack)| {
+            let index = (subset.0.wrapping_mul(magic) >> shift) as usize;
+            match table[index] {
+                Some(stored_attack) => stored_attack == *attack,
+                None => {
+                    table[index] = Some(*attack);
+                    true
+                }
+            }
+        });
+
+        if collision_free {
+            return magic;
+        }
+    }
+}
+
+fn print_magics(name: &str, directions: &[(i8, i8)], random: &mut Random) {
+    println!("pub static {}: [u64; 64] = [", name);
+    for square in 0..64 {
+        println!(
+            "    {:#018x},",
+            find_magic(&Square(square), directions, random)
+        );
+    }
+    println!("];");
+}
+
+fn main() {
+    let seed = match env::args().nth(1) {
+        Some(seed) => seed.parse::<u64>().expect("seed has to be a number"),
+        None => 0x45a1b2c3d4e5f607,
+    };
+    let mut random = Random(seed.max(1));
+
+    println!("// generated by `cargo run --release --bin find_magics`");
+    print_magics("ROOK_MAGICS", &ROOK_DIRECTIONS, &mut random);
+    println!();
+    print_magics("BISHOP_MAGICS", &BISHOP_DIRECTIONS, &mut random);
+}
@@ -1,14 +1,228 @@
 use lazy_static::lazy_static;
 
-use crate::{moves::Square, piece::PieceType, position::Color, utils::log2n};
+use crate::{moves::Square, piece::PieceType, position::Color};
 
 use core::fmt;
-use std::{char, collections::HashMap, fmt::format, ops::BitOrAssign, usize};
+use std::{
+    char,
+    collections::HashMap,
+    fmt::format,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr},
+    slice::Iter,
+};
+
+/*
+    A set of squares, bit i standing for Square(i), a1 = 0 to h8 = 63. The bit operators
+    work on the sets, count, lsb and msb on the squares in them
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    pub fn from_square(square: &Square) -> Bitboard {
+        return Bitboard(1 << square.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    pub fn count(&self) -> u32 {
+        return self.0.count_ones();
+    }
+
+    pub fn contains(&self, square: &Square) -> bool {
+        return self.0 & (1 << square.0) != 0;
+    }
+
+    // the square with the lowest index, the bitboard must not be empty
+    pub fn lsb(&self) -> Square {
+        debug_assert!(!self.is_empty(), "lsb of an empty bitboard");
+        return Square(self.0.trailing_zeros() as u8);
+    }
+
+    // the square with the highest index, the bitboard must not be empty
+    pub fn msb(&self) -> Square {
+        debug_assert!(!self.is_empty(), "msb of an empty bitboard");
+        return Square(63 - self.0.leading_zeros() as u8);
+    }
+
+    // removes the lsb square and returns it
+    pub fn pop_lsb(&mut self) -> Square {
+        let square = self.lsb();
+        self.0 &= self.0 - 1;
+        return square;
+    }
+
+    // the squares of the bitboard from a1 to h8
+    pub fn squares(&self) -> Squares {
+        return Squares(*self);
+    }
+
+    // every square moved one step, squares leaving the board are dropped
+    pub fn north(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::North]);
+    }
+
+    pub fn south(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::South]);
+    }
+
+    pub fn east(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::East]);
+    }
+
+    pub fn west(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::West]);
+    }
+
+    pub fn north_east(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::NorthEast]);
+    }
+
+    pub fn north_west(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::NorthWest]);
+    }
+
+    pub fn south_east(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::SouthEast]);
+    }
+
+    pub fn south_west(&self) -> Bitboard {
+        return shift_direction(self, &[Direction::SouthWest]);
+    }
+}
+
+pub struct Squares(Bitboard);
 
-pub type Bitboard = u64;
+impl Iterator for Squares {
+    type Item = Square;
 
-fn left_most_significant_square(bitboard: &Bitboard) -> Square {
-    return Square(log2n(&(bitboard & bitboard.wrapping_neg())).unwrap() as u8);
+    fn next(&mut self) -> Option<Square> {
+        if self.0.is_empty() {
+            return None;
+        }
+        return Some(self.0.pop_lsb());
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.0.count() as usize;
+        return (count, Some(count));
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = Squares;
+
+    fn into_iter(self) -> Squares {
+        return self.squares();
+    }
+}
+
+// the operators for every mix of values and references, like the ones of u64
+macro_rules! bitboard_operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $operator:tt) => {
+        impl $trait for Bitboard {
+            type Output = Bitboard;
+
+            fn $method(self, rhs: Bitboard) -> Bitboard {
+                return Bitboard(self.0 $operator rhs.0);
+            }
+        }
+
+        impl $trait<&Bitboard> for Bitboard {
+            type Output = Bitboard;
+
+            fn $method(self, rhs: &Bitboard) -> Bitboard {
+                return Bitboard(self.0 $operator rhs.0);
+            }
+        }
+
+        impl $trait<Bitboard> for &Bitboard {
+            type Output = Bitboard;
+
+            fn $method(self, rhs: Bitboard) -> Bitboard {
+                return Bitboard(self.0 $operator rhs.0);
+            }
+        }
+
+        impl $trait<&Bitboard> for &Bitboard {
+            type Output = Bitboard;
+
+            fn $method(self, rhs: &Bitboard) -> Bitboard {
+                return Bitboard(self.0 $operator rhs.0);
+            }
+        }
+
+        impl $assign_trait for Bitboard {
+            fn $assign_method(&mut self, rhs: Bitboard) {
+                self.0 = self.0 $operator rhs.0;
+            }
+        }
+
+        impl $assign_trait<&Bitboard> for Bitboard {
+            fn $assign_method(&mut self, rhs: &Bitboard) {
+                self.0 = self.0 $operator rhs.0;
+            }
+        }
+    };
+}
+
+bitboard_operator!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bitboard_operator!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bitboard_operator!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        return Bitboard(!self.0);
+    }
+}
+
+impl Not for &Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        return Bitboard(!self.0);
+    }
+}
+
+impl Shl<u8> for Bitboard {
+    type Output = Bitboard;
+
+    fn shl(self, rhs: u8) -> Bitboard {
+        return Bitboard(self.0 << rhs);
+    }
+}
+
+impl Shr<u8> for Bitboard {
+    type Output = Bitboard;
+
+    fn shr(self, rhs: u8) -> Bitboard {
+        return Bitboard(self.0 >> rhs);
+    }
+}
+
+// the board from the 8th rank down, as seen by white
+impl fmt::Debug for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bitboard_string = String::from("");
+        for rank in (0..8).rev() {
+            for file in 0..8 {
+                match self.contains(&Square(rank * 8 + file)) {
+                    true => bitboard_string += "1 ",
+                    false => bitboard_string += ". ",
+                }
+            }
+            bitboard_string += "\n";
+        }
+        f.write_str(&bitboard_string)
+    }
 }
 
 fn remove_pieces(bitboard: &Bitboard, our_squares: &Bitboard) -> Bitboard {
@@ -37,31 +251,102 @@ fn shift_direction(bitboard: &Bitboard, directions: &[Direction]) -> Bitboard {
 
 fn bit_shift(bitboard: &Bitboard, n: i64) -> Bitboard {
     if n >= 0 {
-        return bitboard << n;
+        return Bitboard(bitboard.0 << n);
     } else {
-        return bitboard >> -n;
+        return Bitboard(bitboard.0 >> -n);
     }
 }
 
-#[derive(Debug)]
-pub struct PieceBitboard(pub HashMap<PieceType, Bitboard>);
+// a piece of one side, as stored in the mailbox
+pub type Piece = (Color, PieceType);
+
+/*
+    Bitboards of every piece of both sides, indexed by color and piece type. The occupancy of
+    each side, of the whole board and the piece on every square (the mailbox) are kept up to
+    date by add_piece and remove_piece, so the queries below don't have to combine bitboards
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecePlacement {
+    pieces: [[Bitboard; 6]; 2],
+    occupancy: [Bitboard; 2],
+    all_occupancy: Bitboard,
+    mailbox: [Option<Piece>; 64],
+}
+
+impl PiecePlacement {
+    // an empty board
+    pub fn new() -> PiecePlacement {
+        return PiecePlacement {
+            pieces: [[Bitboard::EMPTY; 6]; 2],
+            occupancy: [Bitboard::EMPTY; 2],
+            all_occupancy: Bitboard::EMPTY,
+            mailbox: [None; 64],
+        };
+    }
+
+    pub fn pieces(&self, color: &Color, piece: &PieceType) -> Bitboard {
+        return self.pieces[*color as usize][*piece as usize];
+    }
+
+    pub fn occupancy(&self, color: &Color) -> Bitboard {
+        return self.occupancy[*color as usize];
+    }
+
+    pub fn all_occupancy(&self) -> Bitboard {
+        return self.all_occupancy;
+    }
 
-#[derive(Debug)]
-pub struct PiecePlacement(pub HashMap<Color, PieceBitboard>);
+    pub fn piece_on(&self, square: &Square) -> Option<Piece> {
+        return self.mailbox[square.0 as usize];
+    }
 
-static NOT_A_FILE: Bitboard = 0xfefefefefefefefe;
-static NOT_H_FILE: Bitboard = 0x7f7f7f7f7f7f7f7f;
-static NOT_AB_FILE: Bitboard = 0xFCFCFCFCFCFCFCFC;
-static NOT_GH_FILE: Bitboard = 0x3F3F3F3F3F3F3F3F;
+    pub fn add_piece(&mut self, color: &Color, piece: &PieceType, square: &Square) {
+        let square_bb = Bitboard::from_square(square);
+        self.pieces[*color as usize][*piece as usize] |= square_bb;
+        self.occupancy[*color as usize] |= square_bb;
+        self.all_occupancy |= square_bb;
+        self.mailbox[square.0 as usize] = Some((*color, *piece));
+    }
+
+    pub fn remove_piece(&mut self, color: &Color, piece: &PieceType, square: &Square) {
+        debug_assert_eq!(
+            self.mailbox[square.0 as usize],
+            Some((*color, *piece)),
+            "removing a piece that isn't on {}",
+            square
+        );
+        let square_bb = Bitboard::from_square(square);
+        self.pieces[*color as usize][*piece as usize] &= !square_bb;
+        self.occupancy[*color as usize] &= !square_bb;
+        self.all_occupancy &= !square_bb;
+        self.mailbox[square.0 as usize] = None;
+    }
+}
+
+impl Default for PiecePlacement {
+    fn default() -> Self {
+        return PiecePlacement::new();
+    }
+}
+
+static NOT_A_FILE: Bitboard = Bitboard(0xfefefefefefefefe);
+static NOT_H_FILE: Bitboard = Bitboard(0x7f7f7f7f7f7f7f7f);
+static NOT_AB_FILE: Bitboard = Bitboard(0xFCFCFCFCFCFCFCFC);
+static NOT_GH_FILE: Bitboard = Bitboard(0x3F3F3F3F3F3F3F3F);
+
+pub static RANK_1: Bitboard = Bitboard(0x00000000000000ff);
+pub static RANK_2: Bitboard = Bitboard(0x000000000000ff00);
+pub static RANK_7: Bitboard = Bitboard(0x00ff000000000000);
+pub static RANK_8: Bitboard = Bitboard(0xff00000000000000);
 
 lazy_static! {
     pub static ref KING_ATTACKS: [Bitboard; 64] = {
-        let mut king_attacks: [Bitboard; 64] = [0; 64];
+        let mut king_attacks: [Bitboard; 64] = [Bitboard::EMPTY; 64];
 
-        for i in 0..64 {
-            let square_bb: Bitboard = 1 << i;
+        for (i, king_attack) in king_attacks.iter_mut().enumerate() {
+            let square_bb = Bitboard(1 << i);
 
-            king_attacks[i] = shift_direction(&square_bb, &[Direction::South])
+            *king_attack = shift_direction(&square_bb, &[Direction::South])
                 | shift_direction(&square_bb, &[Direction::North])
                 | shift_direction(&square_bb, &[Direction::East])
                 | shift_direction(&square_bb, &[Direction::West])
@@ -74,12 +359,12 @@ lazy_static! {
         king_attacks
     };
     pub static ref KNIGHT_ATTACKS: [Bitboard; 64] = {
-        let mut knight_attacks: [Bitboard; 64] = [0; 64];
+        let mut knight_attacks: [Bitboard; 64] = [Bitboard::EMPTY; 64];
 
-        for i in 0..64 {
-            let square_bb: Bitboard = 1 << i;
+        for (i, knight_attack) in knight_attacks.iter_mut().enumerate() {
+            let square_bb = Bitboard(1 << i);
 
-            knight_attacks[i] =
+            *knight_attack =
                 shift_direction(&square_bb, &[Direction::South, Direction::SouthWest])
                     | shift_direction(&square_bb, &[Direction::South, Direction::SouthEast])
                     | shift_direction(&square_bb, &[Direction::East, Direction::SouthEast])
@@ -94,10 +379,11 @@ lazy_static! {
     };
     pub static ref PAWN_ATTACKS: HashMap<Color, [Bitboard; 64]> = {
         let mut pawn_attacks: HashMap<Color, [Bitboard; 64]> = HashMap::new();
-        let (mut white_pawn_attacks, mut black_pawn_attacks) = ([0; 64], [0; 64]);
+        let (mut white_pawn_attacks, mut black_pawn_attacks) =
+            ([Bitboard::EMPTY; 64], [Bitboard::EMPTY; 64]);
 
         for i in 0..64 {
-            let square_bb: Bitboard = 1 << i;
+            let square_bb = Bitboard(1 << i);
 
             white_pawn_attacks[i] = shift_direction(&square_bb, &[Direction::NorthWest])
                 | shift_direction(&square_bb, &[Direction::NorthEast]);
@@ -111,6 +397,50 @@ lazy_static! {
 
         pawn_attacks
     };
+    // squares strictly between two squares sharing a rank, file or diagonal
+    pub static ref SQUARES_BETWEEN: [[Bitboard; 64]; 64] = {
+        let mut squares_between: [[Bitboard; 64]; 64] = [[Bitboard::EMPTY; 64]; 64];
+
+        for (i, between) in squares_between.iter_mut().enumerate() {
+            for direction in Direction::iterator() {
+                let mut ray = Bitboard::EMPTY;
+                let mut square_bb = shift_direction(&Bitboard(1 << i), &[*direction]);
+                while !square_bb.is_empty() {
+                    between[square_bb.lsb().0 as usize] = ray;
+                    ray |= square_bb;
+                    square_bb = shift_direction(&square_bb, &[*direction]);
+                }
+            }
+        }
+
+        squares_between
+    };
+    // the whole line, edge to edge, through two squares sharing a rank, file or diagonal
+    pub static ref LINE_THROUGH: [[Bitboard; 64]; 64] = {
+        let mut line_through: [[Bitboard; 64]; 64] = [[Bitboard::EMPTY; 64]; 64];
+
+        for (i, line) in line_through.iter_mut().enumerate() {
+            for direction in Direction::iterator() {
+                let full_line =
+                    Bitboard(1 << i) | ray(i, *direction) | ray(i, direction.opposite());
+                for square in ray(i, *direction) {
+                    line[square.0 as usize] = full_line;
+                }
+            }
+        }
+
+        line_through
+    };
+}
+
+fn ray(square: usize, direction: Direction) -> Bitboard {
+    let mut ray = Bitboard::EMPTY;
+    let mut square_bb = shift_direction(&Bitboard(1 << square), &[direction]);
+    while !square_bb.is_empty() {
+        ray |= square_bb;
+        square_bb = shift_direction(&square_bb, &[direction]);
+    }
+    return ray;
 }
 
 #[derive(Clone, Copy)]
@@ -126,13 +456,42 @@ enum Direction {
     NorthWest = Direction::North as isize + Direction::West as isize,
 }
 
+impl Direction {
+    fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+            Direction::NorthEast => Direction::SouthWest,
+            Direction::SouthEast => Direction::NorthWest,
+            Direction::SouthWest => Direction::NorthEast,
+            Direction::NorthWest => Direction::SouthEast,
+        }
+    }
+
+    fn iterator() -> Iter<'static, Direction> {
+        static DIRECTIONS: [Direction; 8] = [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+            Direction::NorthEast,
+            Direction::SouthEast,
+            Direction::SouthWest,
+            Direction::NorthWest,
+        ];
+        DIRECTIONS.iter()
+    }
+}
+
 impl fmt::Display for PiecePlacement {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut piece_vectors: Vec<Vec<char>> = Vec::new();
         for color in Color::iterator() {
             for piece in PieceType::iterator() {
                 piece_vectors.push(replace_piece(
-                    format!("{:64b}", self.0.get(color).unwrap().0.get(piece).unwrap())
+                    format!("{:64b}", self.pieces(color, piece).0)
                         .chars()
                         .collect(),
                     piece.piece_rep(color),
@@ -185,22 +544,3 @@ fn add_char_vecs(char_vectors: Vec<Vec<char>>) -> Vec<char> {
     }
     return aggregated_vector;
 }
-
-pub fn bitboard_representation(bb: Bitboard) -> String {
-    let mut bitboard_aux_string = format!("{:0>64b}", bb);
-    bitboard_aux_string = bitboard_aux_string.replace("0", ".");
-
-    let mut bitboard_string = String::from("");
-    for i in 0..8 {
-        for j in (0..8).rev() {
-            let index = i * 8 + j;
-            bitboard_string = format!(
-                "{}{} ",
-                bitboard_string,
-                bitboard_aux_string.chars().nth(index).unwrap(),
-            )
-        }
-        bitboard_string = format!("{}\n", bitboard_string)
-    }
-    return bitboard_string;
-}
@@ -0,0 +1,66 @@
+use crate::{fen_parser::parse_fen, position::Position};
+
+// the 10 ways to place the two knights on the 5 squares left after the bishops and queen
+static KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+/*
+    The back rank of the Chess960 starting position with the given number, from the a file
+    https://www.chessprogramming.org/Reinhard_Scharnagl#Chess960Numbering
+    The number picks the light squared bishop, the dark squared bishop, the queen on one of the
+    6 free squares and the knights on the remaining 5, the king goes between the two rooks on
+    the last 3. 518 is the standard starting position
+*/
+pub fn back_rank(id: u16) -> Option<[char; 8]> {
+    if id > 959 {
+        return None;
+    }
+
+    let mut back_rank: [Option<char>; 8] = [None; 8];
+    let mut n = id as usize;
+    back_rank[2 * (n % 4) + 1] = Some('b');
+    n /= 4;
+    back_rank[2 * (n % 4)] = Some('b');
+    n /= 4;
+
+    let free_files = |back_rank: &[Option<char>; 8]| -> Vec<usize> {
+        return (0..8).filter(|file| back_rank[*file].is_none()).collect();
+    };
+    back_rank[free_files(&back_rank)[n % 6]] = Some('q');
+    n /= 6;
+
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[n];
+    let files = free_files(&back_rank);
+    back_rank[files[first_knight]] = Some('n');
+    back_rank[files[second_knight]] = Some('n');
+
+    for (file, piece) in free_files(&back_rank).into_iter().zip(['r', 'k', 'r']) {
+        back_rank[file] = Some(piece);
+    }
+    return Some(back_rank.map(|piece| piece.unwrap()));
+}
+
+// FEN of the Chess960 starting position with the given number, castling rights in X-FEN
+pub fn start_position_fen(id: u16) -> Option<String> {
+    let black_pieces: String = back_rank(id)?.iter().collect();
+    return Some(format!(
+        "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+        black_pieces,
+        black_pieces.to_ascii_uppercase()
+    ));
+}
+
+pub fn start_position(id: u16) -> Option<Position> {
+    let fen = start_position_fen(id)?;
+    return Some(parse_fen(&fen).expect("Chess960 starting positions are valid FENs"));
+}
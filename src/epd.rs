@@ -0,0 +1,408 @@
+use core::fmt;
+
+use crate::{
+    fen_parser::{parse_fen_lenient, FenError},
+    moves::Move,
+    position::Position,
+    san::SanError,
+};
+
+/*
+    Extended Position Description: the first four FEN fields followed by operations, each an
+    opcode, its operands and a semicolon
+        2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";
+    https://www.chessprogramming.org/Extended_Position_Description
+
+    Perft suite style records with the move clocks after the fields and a semicolon in front
+    of every operation are read as well
+*/
+
+// opcodes whose operand is a string, written in quotes even when it has no spaces
+static STRING_OPCODES: [&str; 11] = [
+    "id", "c0", "c1", "c2", "c3", "c4", "c5", "c6", "c7", "c8", "c9",
+];
+
+// what is wrong with an EPD record, offsets count characters from the start of the record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdError {
+    // the number of position fields found
+    MissingFields(usize),
+    InvalidPosition(FenError),
+    // offset of the opening quote
+    UnterminatedString {
+        offset: usize,
+    },
+    // an opcode not starting with a letter or not made of letters, digits and '_'
+    InvalidOpcode {
+        offset: usize,
+    },
+    DuplicateOpcode {
+        offset: usize,
+    },
+    // a move clock operand of hmvc or fmvn that is not a number
+    InvalidClock {
+        offset: usize,
+    },
+    // an operation set_operation refuses, with the index of the bad operand when the opcode
+    // itself is fine
+    InvalidOperation {
+        opcode: String,
+        operand: Option<usize>,
+    },
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingFields(count) => {
+                write!(f, "EPD has {} position fields instead of 4", count)
+            }
+            Self::InvalidPosition(error) => write!(f, "invalid EPD position, {}", error),
+            Self::UnterminatedString { offset } => {
+                write!(f, "string starting at offset {} is not closed", offset)
+            }
+            Self::InvalidOpcode { offset } => write!(f, "invalid opcode at offset {}", offset),
+            Self::DuplicateOpcode { offset } => {
+                write!(f, "opcode at offset {} appears more than once", offset)
+            }
+            Self::InvalidClock { offset } => {
+                write!(f, "invalid move clock operand at offset {}", offset)
+            }
+            Self::InvalidOperation {
+                opcode,
+                operand: None,
+            } => write!(f, "invalid opcode {:?}", opcode),
+            Self::InvalidOperation {
+                opcode,
+                operand: Some(index),
+            } => write!(f, "invalid operand {} of {}", index, opcode),
+        }
+    }
+}
+
+impl std::error::Error for EpdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidPosition(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epd {
+    // the move clocks come from the hmvc and fmvn operations or perft suite style clock
+    // fields, 0 and 1 without them
+    pub position: Position,
+    // every opcode once, in the order the operations were read or set
+    operations: Vec<(String, Vec<String>)>,
+}
+
+impl Epd {
+    pub fn new(position: Position) -> Epd {
+        return Epd {
+            position,
+            operations: Vec::new(),
+        };
+    }
+
+    pub fn operations(&self) -> &[(String, Vec<String>)] {
+        return &self.operations;
+    }
+
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        return self
+            .operations
+            .iter()
+            .find(|(name, _)| name == opcode)
+            .map(|(_, operands)| operands.as_slice());
+    }
+
+    /*
+        Replaces the operands of the opcode where it is or adds it at the end, hmvc and fmvn
+        set the move clocks of the position as well. Operations parse_epd would refuse leave
+        the record as it is
+    */
+    pub fn set_operation(&mut self, opcode: &str, operands: Vec<String>) -> Result<(), EpdError> {
+        if let Err(operand) = check_operation(opcode, &operands) {
+            return Err(EpdError::InvalidOperation {
+                opcode: opcode.to_string(),
+                operand,
+            });
+        }
+        self.insert_operation(opcode, operands);
+        return Ok(());
+    }
+
+    fn insert_operation(&mut self, opcode: &str, operands: Vec<String>) {
+        let clock = operands
+            .first()
+            .and_then(|operand| operand.parse::<u16>().ok());
+        match (opcode, clock) {
+            ("hmvc", Some(clock)) => self.position.half_move_clock = clock,
+            ("fmvn", Some(clock)) => self.position.full_move_number = clock,
+            _ => {}
+        }
+
+        match self.operations.iter_mut().find(|(name, _)| name == opcode) {
+            Some((_, old_operands)) => *old_operands = operands,
+            None => self.operations.push((opcode.to_string(), operands)),
+        }
+    }
+
+    pub fn remove_operation(&mut self, opcode: &str) -> Option<Vec<String>> {
+        let index = self
+            .operations
+            .iter()
+            .position(|(name, _)| name == opcode)?;
+        return Some(self.operations.remove(index).1);
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        return self.string_operand("id");
+    }
+
+    // the comments c0 to c9
+    pub fn comment(&self, index: u8) -> Option<&str> {
+        return self.string_operand(&format!("c{}", index));
+    }
+
+    // best moves (bm) in SAN, any of them solves a test position
+    pub fn best_moves(&self) -> Result<Vec<Move>, SanError> {
+        return self.moves("bm");
+    }
+
+    // avoid moves (am) in SAN, none of them may be played
+    pub fn avoid_moves(&self) -> Result<Vec<Move>, SanError> {
+        return self.moves("am");
+    }
+
+    // centipawn evaluation (ce) from the point of view of the side to move
+    pub fn centipawn_evaluation(&self) -> Option<i32> {
+        return self.string_operand("ce")?.parse::<i32>().ok();
+    }
+
+    // analysis count depth (acd) in plies
+    pub fn analysis_depth(&self) -> Option<u32> {
+        return self.string_operand("acd")?.parse::<u32>().ok();
+    }
+
+    // predicted variation (pv) in SAN, every move played from the position after the last
+    pub fn principal_variation(&self) -> Result<Vec<Move>, SanError> {
+        let mut position = self.position.clone();
+        let mut moves: Vec<Move> = Vec::new();
+        for san in self.operation("pv").unwrap_or_default() {
+            let mv = position.parse_san(san)?;
+            position.make_move(mv);
+            moves.push(mv);
+        }
+        return Ok(moves);
+    }
+
+    fn string_operand(&self, opcode: &str) -> Option<&str> {
+        return self
+            .operation(opcode)?
+            .first()
+            .map(|operand| operand.as_str());
+    }
+
+    fn moves(&self, opcode: &str) -> Result<Vec<Move>, SanError> {
+        return self
+            .operation(opcode)
+            .unwrap_or_default()
+            .iter()
+            .map(|san| self.position.parse_san(san))
+            .collect();
+    }
+}
+
+/*
+    Writes the four position fields and the operations, quoting string operands. The move
+    clocks of the position go in the hmvc and fmvn operations, which are added at the end
+    when missing and the clock isn't at its default of 0 or 1
+*/
+impl fmt::Display for Epd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fen = self.position.to_fen();
+        let fields: Vec<&str> = fen.split(' ').collect();
+        write!(f, "{}", fields[..4].join(" "))?;
+
+        let clocks = [
+            ("hmvc", self.position.half_move_clock, 0),
+            ("fmvn", self.position.full_move_number, 1),
+        ];
+        for (opcode, operands) in &self.operations {
+            if let Some((_, clock, _)) = clocks.iter().find(|(name, _, _)| name == opcode) {
+                write!(f, " {} {};", opcode, clock)?;
+                continue;
+            }
+
+            write!(f, " {}", opcode)?;
+            for operand in operands {
+                write!(f, " {}", written_operand(opcode, operand))?;
+            }
+            f.write_str(";")?;
+        }
+        for (opcode, clock, default) in clocks {
+            if clock != default && self.operation(opcode).is_none() {
+                write!(f, " {} {};", opcode, clock)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+// the operand as written, quoted for string opcodes and when it can't be read as one word
+fn written_operand(opcode: &str, operand: &str) -> String {
+    let needs_quotes = operand.is_empty()
+        || operand
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, ';' | '"' | '\\'));
+    if STRING_OPCODES.contains(&opcode) || needs_quotes {
+        return format!("\"{}\"", operand.replace('\\', "\\\\").replace('"', "\\\""));
+    }
+    return operand.to_string();
+}
+
+/*
+    Opcodes start with a letter followed by letters, digits and '_', hmvc and fmvn take one
+    move clock. Refused operations give the index of the bad operand, 0 for a missing clock,
+    or None when the opcode is the problem
+*/
+fn check_operation(opcode: &str, operands: &[String]) -> Result<(), Option<usize>> {
+    let is_valid_opcode = opcode.starts_with(|c: char| c.is_ascii_alphabetic())
+        && opcode
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_valid_opcode {
+        return Err(None);
+    }
+    if opcode == "hmvc" || opcode == "fmvn" {
+        match operands {
+            [clock] if clock.parse::<u16>().is_ok() => return Ok(()),
+            [clock, _, ..] if clock.parse::<u16>().is_ok() => return Err(Some(1)),
+            _ => return Err(Some(0)),
+        }
+    }
+    return Ok(());
+}
+
+// a word of an EPD record, quoted ones are operands for sure
+struct Word {
+    offset: usize,
+    text: String,
+    quoted: bool,
+}
+
+pub fn parse_epd(epd: &str) -> Result<Epd, EpdError> {
+    let chars: Vec<char> = epd.chars().collect();
+    let mut i = 0;
+    let skip_whitespace = |i: &mut usize| {
+        while *i < chars.len() && chars[*i].is_whitespace() {
+            *i += 1;
+        }
+    };
+
+    // the position fields, then the move clocks if present
+    let mut fields: Vec<String> = Vec::new();
+    loop {
+        skip_whitespace(&mut i);
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != ';' {
+            i += 1;
+        }
+        let field: String = chars[start..i].iter().collect();
+        let is_clock = !field.is_empty() && field.chars().all(|c| c.is_ascii_digit());
+        if field.is_empty() || (fields.len() >= 4 && !is_clock) || fields.len() == 6 {
+            i = start;
+            break;
+        }
+        fields.push(field);
+    }
+    if fields.len() < 4 {
+        return Err(EpdError::MissingFields(fields.len()));
+    }
+    let mut epd =
+        Epd::new(parse_fen_lenient(&fields.join(" ")).map_err(EpdError::InvalidPosition)?);
+
+    let mut operation: Vec<Word> = Vec::new();
+    loop {
+        skip_whitespace(&mut i);
+        if i == chars.len() || chars[i] == ';' {
+            if !operation.is_empty() {
+                add_operation(&mut epd, std::mem::take(&mut operation))?;
+            }
+            if i == chars.len() {
+                break;
+            }
+            i += 1;
+            continue;
+        }
+
+        let offset = i;
+        if chars[i] == '"' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(EpdError::UnterminatedString { offset }),
+                    Some('"') => break,
+                    Some('\\') if i + 1 < chars.len() => {
+                        text.push(chars[i + 1]);
+                        i += 1;
+                    }
+                    Some(c) => text.push(*c),
+                }
+                i += 1;
+            }
+            i += 1;
+            operation.push(Word {
+                offset,
+                text,
+                quoted: true,
+            });
+        } else {
+            while i < chars.len() && !chars[i].is_whitespace() && chars[i] != ';' {
+                i += 1;
+            }
+            operation.push(Word {
+                offset,
+                text: chars[offset..i].iter().collect(),
+                quoted: false,
+            });
+        }
+    }
+    return Ok(epd);
+}
+
+fn add_operation(epd: &mut Epd, words: Vec<Word>) -> Result<(), EpdError> {
+    let opcode = &words[0];
+    if opcode.quoted {
+        return Err(EpdError::InvalidOpcode {
+            offset: opcode.offset,
+        });
+    }
+    let operands: Vec<String> = words[1..].iter().map(|word| word.text.clone()).collect();
+    match check_operation(&opcode.text, &operands) {
+        Ok(()) => {}
+        Err(None) => {
+            return Err(EpdError::InvalidOpcode {
+                offset: opcode.offset,
+            })
+        }
+        // a missing clock is reported at the opcode
+        Err(Some(index)) => {
+            return Err(EpdError::InvalidClock {
+                offset: words.get(index + 1).unwrap_or(opcode).offset,
+            })
+        }
+    }
+    if epd.operation(&opcode.text).is_some() {
+        return Err(EpdError::DuplicateOpcode {
+            offset: opcode.offset,
+        });
+    }
+
+    epd.insert_operation(&opcode.text, operands);
+    return Ok(());
+}
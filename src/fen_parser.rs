@@ -1,204 +1,618 @@
+use core::fmt;
 use std::collections::HashMap;
 use std::io::Error;
 use std::{char, io::Empty};
 
 use crate::{
-    bitboard::{Bitboard, PieceBitboard, PiecePlacement},
+    bitboard::{Bitboard, PiecePlacement},
     moves::Square,
     piece::PieceType,
     position::{CastlingRights, CastlingTypes, Color, Position},
+    validation::PositionError,
 };
 
-pub fn parse_fen(fen: &str) -> Result<Position, String> {
-    let components: Vec<&str> = fen.split_whitespace().collect();
+pub static INITIAL_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
-    if components.len() != 6 {
-        return Err(String::from("Number of components in fen!=6"));
+static COMPONENTS: [&str; 6] = [
+    "piece placement",
+    "active color",
+    "castling rights",
+    "en passant target",
+    "half move clock",
+    "full move number",
+];
+
+/*
+    What is wrong with a FEN. Components are indexed from 0 in the order of COMPONENTS,
+    offsets count characters from the start of the FEN
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    // the number of components found
+    ComponentCount(usize),
+    UnexpectedCharacter {
+        component: usize,
+        offset: usize,
+        character: char,
+    },
+    // a rank spanning more or less than 8 files, offset of where it overflows or ends
+    RankLength {
+        rank: u8,
+        offset: usize,
+    },
+    // a piece placement with more or less than 8 ranks, offset of the 9th rank or the end
+    RankCount {
+        offset: usize,
+    },
+    // a component cut short, e.g. an en passant target without its rank
+    Incomplete {
+        component: usize,
+        offset: usize,
+    },
+    // a move clock too large for a u16
+    InvalidNumber {
+        component: usize,
+        offset: usize,
+    },
+    // a well formed FEN of a position breaking the rules of chess, see parse_fen_strict
+    InvalidPosition(PositionError),
+}
+
+impl FenError {
+    pub fn component(&self) -> Option<usize> {
+        match self {
+            Self::UnexpectedCharacter { component, .. }
+            | Self::Incomplete { component, .. }
+            | Self::InvalidNumber { component, .. } => Some(*component),
+            Self::RankLength { .. } | Self::RankCount { .. } => Some(0),
+            Self::ComponentCount(_) | Self::InvalidPosition(_) => None,
+        }
     }
 
-    return Ok(Position {
-        piece_placement: parse_piece_placement(components[0])?,
-        active_color: parse_active_color(components[1])?,
-        castling_rights: parse_castling_rights(components[2])?,
-        en_passant_target: parse_en_passant_target(components[3])?,
-        half_move_clock: parse_u16_int(components[4])?,
-        full_move_number: parse_u16_int(components[5])?,
-    });
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Self::UnexpectedCharacter { offset, .. }
+            | Self::RankLength { offset, .. }
+            | Self::RankCount { offset }
+            | Self::Incomplete { offset, .. }
+            | Self::InvalidNumber { offset, .. } => Some(*offset),
+            Self::ComponentCount(_) | Self::InvalidPosition(_) => None,
+        }
+    }
 }
 
-fn parse_piece_placement(piece_placement_str: &str) -> Result<PiecePlacement, String> {
-    let ranks: Vec<&str> = piece_placement_str.split("/").collect();
+fn component_name(component: &usize) -> &str {
+    return COMPONENTS.get(*component).unwrap_or(&"end of the FEN");
+}
 
-    if ranks.len() != 8 {
-        return Err(String::from("number of ranks in fen != 8"));
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ComponentCount(count) => write!(f, "FEN has {} components instead of 6", count),
+            Self::UnexpectedCharacter {
+                component,
+                offset,
+                character,
+            } => write!(
+                f,
+                "unexpected character {:?} in the {} at offset {}",
+                character,
+                component_name(component),
+                offset
+            ),
+            Self::RankLength { rank, offset } => write!(
+                f,
+                "rank {} doesn't span 8 files, at offset {}",
+                rank, offset
+            ),
+            Self::RankCount { offset } => write!(
+                f,
+                "piece placement doesn't have 8 ranks, at offset {}",
+                offset
+            ),
+            Self::Incomplete { component, offset } => write!(
+                f,
+                "{} ends early at offset {}",
+                component_name(component),
+                offset
+            ),
+            Self::InvalidNumber { component, offset } => write!(
+                f,
+                "{} at offset {} is too large",
+                component_name(component),
+                offset
+            ),
+            Self::InvalidPosition(err) => write!(f, "{}", err),
+        }
     }
+}
 
-    let mut white_pieces: HashMap<PieceType, Bitboard> = HashMap::new();
-    let mut black_pieces: HashMap<PieceType, Bitboard> = HashMap::new();
+impl std::error::Error for FenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidPosition(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
-    for piece in PieceType::iterator() {
-        white_pieces.insert(*piece, 0);
-        black_pieces.insert(*piece, 0);
+pub fn parse_fen(fen: &str) -> Result<Position, FenError> {
+    return parse(fen, false);
+}
+
+// parse_fen that also takes FENs the way EPD files and web exports tend to write them,
+// without the move clocks, which then default to 0 and 1
+pub fn parse_fen_lenient(fen: &str) -> Result<Position, FenError> {
+    return parse(fen, true);
+}
+
+// parse_fen that also rejects positions breaking the rules of chess, see Position::validate
+pub fn parse_fen_strict(fen: &str) -> Result<Position, FenError> {
+    let position = parse_fen(fen)?;
+    match position.validate() {
+        Ok(()) => return Ok(position),
+        Err(err) => return Err(FenError::InvalidPosition(err)),
     }
+}
+
+fn parse(fen: &str, lenient: bool) -> Result<Position, FenError> {
+    let components = split_components(fen);
 
-    for i in 0..8 {
+    let component_count = match lenient {
+        true => 4..=6,
+        false => 6..=6,
+    };
+    if !component_count.contains(&components.len()) {
+        return Err(FenError::ComponentCount(components.len()));
+    }
+
+    let piece_placement = parse_piece_placement(&components[0])?;
+    let mut position = Position {
+        active_color: parse_active_color(&components[1])?,
+        castling_rights: parse_castling_rights(&components[2], &piece_placement)?,
+        en_passant_target: parse_en_passant_target(&components[3])?,
+        half_move_clock: match components.get(4) {
+            Some(component) => parse_u16_int(component, 4)?,
+            None => 0,
+        },
+        full_move_number: match components.get(5) {
+            Some(component) => parse_u16_int(component, 5)?,
+            None => 1,
+        },
+        piece_placement,
+        hash: 0,
+    };
+    position.hash = position.compute_hash();
+
+    return Ok(position);
+}
+
+// the components of the FEN with the offset of their first character, split on any whitespace
+fn split_components(fen: &str) -> Vec<(usize, &str)> {
+    let mut components: Vec<(usize, &str)> = Vec::new();
+    // (offset, byte index) of the first character of the component being read
+    let mut component_start: Option<(usize, usize)> = None;
+
+    // the end of the FEN closes the last component like a space
+    let characters = fen.char_indices().chain(std::iter::once((fen.len(), ' ')));
+    for (offset, (index, c)) in characters.enumerate() {
+        if !c.is_whitespace() {
+            component_start.get_or_insert((offset, index));
+            continue;
+        }
+
+        if let Some((start_offset, start_index)) = component_start.take() {
+            components.push((start_offset, &fen[start_index..index]));
+        }
+    }
+
+    return components;
+}
+
+fn unexpected_character(component: usize, offset: usize, character: char) -> FenError {
+    return FenError::UnexpectedCharacter {
+        component,
+        offset,
+        character,
+    };
+}
+
+fn parse_piece_placement(
+    (offset, piece_placement_str): &(usize, &str),
+) -> Result<PiecePlacement, FenError> {
+    let ranks: Vec<&str> = piece_placement_str.split("/").collect();
+
+    // offset of the first character of every rank
+    let mut rank_offsets: Vec<usize> = vec![*offset];
+    for rank in &ranks {
+        rank_offsets.push(rank_offsets[rank_offsets.len() - 1] + rank.chars().count() + 1);
+    }
+
+    if ranks.len() != 8 {
+        return Err(FenError::RankCount {
+            offset: rank_offsets[ranks.len().min(8)] - 1,
+        });
+    }
+
+    let mut piece_placement = PiecePlacement::new();
+
+    for (i, rank) in ranks.iter().enumerate() {
         let mut k = 0;
-        for c in ranks[i].chars() {
+        for (j, c) in rank.chars().enumerate() {
+            if k >= 8 {
+                return Err(FenError::RankLength {
+                    rank: 8 - i as u8,
+                    offset: rank_offsets[i] + j,
+                });
+            }
             let index = 8 * (7 - i) + k;
             match c {
                 'P' => {
-                    *white_pieces.entry(PieceType::Pawn).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::White,
+                        &PieceType::Pawn,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'N' => {
-                    *white_pieces.entry(PieceType::Knight).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::White,
+                        &PieceType::Knight,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'B' => {
-                    *white_pieces.entry(PieceType::Bishop).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::White,
+                        &PieceType::Bishop,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'R' => {
-                    *white_pieces.entry(PieceType::Rook).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::White,
+                        &PieceType::Rook,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'Q' => {
-                    *white_pieces.entry(PieceType::Queen).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::White,
+                        &PieceType::Queen,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'K' => {
-                    *white_pieces.entry(PieceType::King).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::White,
+                        &PieceType::King,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'p' => {
-                    *black_pieces.entry(PieceType::Pawn).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::Black,
+                        &PieceType::Pawn,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'n' => {
-                    *black_pieces.entry(PieceType::Knight).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::Black,
+                        &PieceType::Knight,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'b' => {
-                    *black_pieces.entry(PieceType::Bishop).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::Black,
+                        &PieceType::Bishop,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'r' => {
-                    *black_pieces.entry(PieceType::Rook).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::Black,
+                        &PieceType::Rook,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'q' => {
-                    *black_pieces.entry(PieceType::Queen).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::Black,
+                        &PieceType::Queen,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 'k' => {
-                    *black_pieces.entry(PieceType::King).or_default() += 1 << index;
+                    piece_placement.add_piece(
+                        &Color::Black,
+                        &PieceType::King,
+                        &Square(index as u8),
+                    );
                     k += 1;
                 }
                 _ => match c.to_string().parse::<usize>() {
-                    Ok(empty_positions) if (empty_positions >= 1 && empty_positions <= 8) => {
+                    Ok(empty_positions) if (1..=8).contains(&empty_positions) => {
                         k += empty_positions
                     }
-                    _ => return Err(format!("char {}: invalid empty space", c)),
+                    _ => return Err(unexpected_character(0, rank_offsets[i] + j, c)),
                 },
             };
         }
+        if k != 8 {
+            return Err(FenError::RankLength {
+                rank: 8 - i as u8,
+                offset: rank_offsets[i + 1] - 1,
+            });
+        }
     }
 
-    let mut piece_placement = HashMap::new();
-    piece_placement.insert(Color::White, PieceBitboard(white_pieces));
-    piece_placement.insert(Color::Black, PieceBitboard(black_pieces));
-
-    return Ok(PiecePlacement(piece_placement));
+    return Ok(piece_placement);
 }
 
-fn parse_active_color(active_color_str: &str) -> Result<Color, String> {
-    match active_color_str {
-        "w" => return Ok(Color::White),
-        "b" => return Ok(Color::Black),
-        _ => return Err(String::from("Invalid active color")),
+fn parse_active_color((offset, active_color_str): &(usize, &str)) -> Result<Color, FenError> {
+    let mut chars = active_color_str.chars();
+    let active_color = match chars.next() {
+        Some('w') => Color::White,
+        Some('b') => Color::Black,
+        Some(c) => return Err(unexpected_character(1, *offset, c)),
+        None => {
+            return Err(FenError::Incomplete {
+                component: 1,
+                offset: *offset,
+            })
+        }
+    };
+    match chars.next() {
+        Some(c) => return Err(unexpected_character(1, offset + 1, c)),
+        None => return Ok(active_color),
     }
 }
 
-fn parse_castling_rights(castling_rights_str: &str) -> Result<Option<CastlingRights>, String> {
-    if castling_rights_str == "-" {
+/*
+    Castling rights in standard FEN (KQkq), X-FEN or Shredder-FEN. X-FEN keeps KQkq for the
+    outermost rook on each side of the king and names the file of any other rook, Shredder-FEN
+    always names the file, HAha for the standard starting position. A file right of the king
+    is a king side right, left of it a queen side one
+*/
+fn parse_castling_rights(
+    (offset, castling_rights_str): &(usize, &str),
+    piece_placement: &PiecePlacement,
+) -> Result<Option<CastlingRights>, FenError> {
+    if *castling_rights_str == "-" {
         return Ok(None);
     }
 
-    if castling_rights_str.len() > 4 {
-        return Err(String::from(
-            "castling rights string contains more than 4 characters",
-        ));
-    }
-
-    let (mut white_king_side_castling_type, mut white_queen_side_castling_type) = (false, false);
-    let (mut black_king_side_castling_type, mut black_queen_side_castling_type) = (false, false);
-
-    for c in castling_rights_str.chars() {
-        match c {
-            'K' => white_king_side_castling_type = true,
-            'Q' => white_queen_side_castling_type = true,
-            'k' => black_king_side_castling_type = true,
-            'q' => black_queen_side_castling_type = true,
-            _ => {
-                return Err(String::from(
-                    "castling rights string contain characters other than K, Q, k, q",
-                ))
+    let mut castling_rights: HashMap<Color, CastlingTypes> = HashMap::new();
+    // every right at most once
+    for (i, c) in castling_rights_str.chars().enumerate() {
+        let color = match c.is_ascii_uppercase() {
+            true => Color::White,
+            false => Color::Black,
+        };
+        let (king_side, rook_file) = match c.to_ascii_lowercase() {
+            'k' => (true, outermost_rook_file(piece_placement, &color, true)),
+            'q' => (false, outermost_rook_file(piece_placement, &color, false)),
+            file @ 'a'..='h' => {
+                let rook_file = file as u8 - b'a';
+                (
+                    rook_file > castling_king_file(piece_placement, &color),
+                    rook_file,
+                )
             }
+            _ => return Err(unexpected_character(2, offset + i, c)),
+        };
+
+        let castling_types = castling_rights
+            .entry(color)
+            .or_insert(CastlingTypes(None, None));
+        let castling_type = match king_side {
+            true => &mut castling_types.0,
+            false => &mut castling_types.1,
+        };
+        if castling_type.is_some() {
+            return Err(unexpected_character(2, offset + i, c));
         }
+        *castling_type = Some(rook_file);
     }
 
-    let mut castling_rights = HashMap::new();
-    if white_king_side_castling_type || white_queen_side_castling_type {
-        castling_rights.insert(
-            Color::White,
-            CastlingTypes(
-                white_king_side_castling_type,
-                white_queen_side_castling_type,
-            ),
-        );
-    }
-    if black_king_side_castling_type || black_queen_side_castling_type {
-        castling_rights.insert(
-            Color::Black,
-            CastlingTypes(
-                black_king_side_castling_type,
-                black_queen_side_castling_type,
-            ),
-        );
-    }
+    return Ok(Some(CastlingRights(castling_rights)));
+}
 
-    if castling_rights.len() == 0 {
-        return Ok(None);
+// file of the king on its home rank, the e file when it has left it
+fn castling_king_file(piece_placement: &PiecePlacement, color: &Color) -> u8 {
+    let home_rank = Bitboard(0xff) << color.home_rank_offset();
+    let king = piece_placement.pieces(color, &PieceType::King) & home_rank;
+    if king.is_empty() {
+        return 4;
     }
+    return king.lsb().0 % 8;
+}
 
-    return Ok(Some(CastlingRights(castling_rights)));
+// file of the home rank rook farthest from the king on the given side, the h or a file if none
+fn outermost_rook_file(piece_placement: &PiecePlacement, color: &Color, king_side: bool) -> u8 {
+    let home_rank_offset = color.home_rank_offset();
+    let rooks = piece_placement.pieces(color, &PieceType::Rook) >> home_rank_offset;
+    let king_file = castling_king_file(piece_placement, color);
+
+    let files: Vec<u8> = match king_side {
+        true => (king_file + 1..8).rev().collect(),
+        false => (0..king_file).collect(),
+    };
+    return files
+        .into_iter()
+        .find(|file| rooks.contains(&Square(*file)))
+        .unwrap_or(if king_side { 7 } else { 0 });
 }
 
-fn parse_en_passant_target(en_passant_target: &str) -> Result<Option<Square>, String> {
-    if en_passant_target == "-" {
+fn parse_en_passant_target(
+    (offset, en_passant_target): &(usize, &str),
+) -> Result<Option<Square>, FenError> {
+    if *en_passant_target == "-" {
         return Ok(None);
     }
 
-    let en_passant_target_chars: Vec<char> = en_passant_target.chars().collect();
-    if en_passant_target_chars.len() != 2 {
-        return Err(String::from("en passant target in wrong format"));
-    }
-
-    let (file, rank) = (
-        en_passant_target_chars[0] as u8 - b'a',
-        en_passant_target_chars[1] as u8 - b'1',
-    );
-
-    if file > 7 || rank > 7 {
-        return Err(String::from("en passant target in wrong format"));
+    let mut chars = en_passant_target.chars();
+    let file = match chars.next() {
+        Some(c @ 'a'..='h') => c as u8 - b'a',
+        Some(c) => return Err(unexpected_character(3, *offset, c)),
+        None => {
+            return Err(FenError::Incomplete {
+                component: 3,
+                offset: *offset,
+            })
+        }
+    };
+    let rank = match chars.next() {
+        Some(c @ '1'..='8') => c as u8 - b'1',
+        Some(c) => return Err(unexpected_character(3, offset + 1, c)),
+        None => {
+            return Err(FenError::Incomplete {
+                component: 3,
+                offset: offset + 1,
+            })
+        }
+    };
+    if let Some(c) = chars.next() {
+        return Err(unexpected_character(3, offset + 2, c));
     }
 
     return Ok(Some(Square(rank * 8 + file)));
 }
 
-fn parse_u16_int(u16_string: &str) -> Result<u16, String> {
+fn parse_u16_int((offset, u16_string): &(usize, &str), component: usize) -> Result<u16, FenError> {
+    if let Some((i, c)) = u16_string
+        .chars()
+        .enumerate()
+        .find(|(_, c)| !c.is_ascii_digit())
+    {
+        return Err(unexpected_character(component, offset + i, c));
+    }
     match u16_string.parse::<u16>() {
         Ok(value) => Ok(value),
-        Err(_) => Err(String::from("Invalid string, couldn't convert to number")),
+        Err(_) => Err(FenError::InvalidNumber {
+            component,
+            offset: *offset,
+        }),
+    }
+}
+
+// how the castling field of a written FEN names the castling rooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastlingNotation {
+    // KQkq unless the rook isn't the outermost one, the same as standard FEN for standard chess
+    XFen,
+    // always the file of the rook
+    Shredder,
+}
+
+impl Position {
+    // the position in Forsyth-Edwards Notation, parse_fen reads it back to the same position
+    pub fn to_fen(&self) -> String {
+        return self.fen().to_string();
+    }
+
+    // FEN with the castling rooks named by their files, HAha for the standard position
+    pub fn to_shredder_fen(&self) -> String {
+        return Fen(self, CastlingNotation::Shredder).to_string();
+    }
+
+    // displays the position as FEN, where Display on Position itself is the verbose dump
+    pub fn fen(&self) -> Fen<'_> {
+        return Fen(self, CastlingNotation::XFen);
+    }
+}
+
+pub struct Fen<'a>(pub &'a Position, pub CastlingNotation);
+
+impl fmt::Display for Fen<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = self.0;
+
+        for rank in (0..8).rev() {
+            let mut empty_squares = 0;
+            for file in 0..8 {
+                match position.piece_placement.piece_on(&Square(rank * 8 + file)) {
+                    Some((color, piece)) => {
+                        if empty_squares > 0 {
+                            write!(f, "{}", empty_squares)?;
+                            empty_squares = 0;
+                        }
+                        write!(f, "{}", piece.piece_char(&color))?;
+                    }
+                    None => empty_squares += 1,
+                }
+            }
+            if empty_squares > 0 {
+                write!(f, "{}", empty_squares)?;
+            }
+            if rank > 0 {
+                write!(f, "/")?;
+            }
+        }
+
+        let active_color = match position.active_color {
+            Color::White => 'w',
+            Color::Black => 'b',
+        };
+        write!(f, " {} ", active_color)?;
+
+        let mut castling_rights = String::new();
+        for color in Color::iterator() {
+            let castling_types = match &position.castling_rights {
+                Some(castling_rights) => castling_rights.0.get(color),
+                None => None,
+            };
+            let castling_types = match castling_types {
+                Some(castling_types) => castling_types,
+                None => continue,
+            };
+
+            for (rook_file, king_side, right_char) in [
+                (castling_types.0, true, 'k'),
+                (castling_types.1, false, 'q'),
+            ] {
+                let rook_file = match rook_file {
+                    Some(rook_file) => rook_file,
+                    None => continue,
+                };
+                let is_outermost =
+                    rook_file == outermost_rook_file(&position.piece_placement, color, king_side);
+                let right_char = match self.1 {
+                    CastlingNotation::XFen if is_outermost => right_char,
+                    _ => (b'a' + rook_file) as char,
+                };
+                castling_rights.push(match color {
+                    Color::White => right_char.to_ascii_uppercase(),
+                    Color::Black => right_char,
+                });
+            }
+        }
+        match castling_rights.is_empty() {
+            true => write!(f, "-")?,
+            false => write!(f, "{}", castling_rights)?,
+        }
+
+        match &position.en_passant_target {
+            Some(en_passant_target) => write!(f, " {}", en_passant_target)?,
+            None => write!(f, " -")?,
+        }
+        write!(
+            f,
+            " {} {}",
+            position.half_move_clock, position.full_move_number
+        )
     }
 }
@@ -0,0 +1,117 @@
+use crate::{
+    fen_parser::{parse_fen, INITIAL_POSITION},
+    move_generator::is_legal,
+    moves::Move,
+    outcome::Outcome,
+    position::{Position, Undo},
+};
+
+/*
+    A game played from a starting position. Keeps what a lone Position can't know: the moves
+    that led to it, the keys of the positions before it for repetitions and the records to
+    take the moves back
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    starting_position: Position,
+    position: Position,
+    moves: Vec<Move>,
+    // keys of the positions before the current one, oldest first
+    hashes: Vec<u64>,
+    undos: Vec<Undo>,
+}
+
+impl Game {
+    pub fn new(starting_position: Position) -> Game {
+        return Game {
+            position: starting_position.clone(),
+            starting_position,
+            moves: Vec::new(),
+            hashes: Vec::new(),
+            undos: Vec::new(),
+        };
+    }
+
+    pub fn starting_position(&self) -> &Position {
+        return &self.starting_position;
+    }
+
+    // the position after the last move
+    pub fn position(&self) -> &Position {
+        return &self.position;
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        return &self.moves;
+    }
+
+    // plays a move of the side to move, refusing illegal ones
+    pub fn push(&mut self, mv: Move) -> Result<(), String> {
+        if !is_legal(&self.position, mv) {
+            return Err(format!("illegal move {}", mv));
+        }
+
+        self.hashes.push(self.position.hash);
+        self.undos.push(self.position.make_move(mv));
+        self.moves.push(mv);
+        return Ok(());
+    }
+
+    // takes back the last move, None at the starting position
+    pub fn pop(&mut self) -> Option<Move> {
+        let mv = self.moves.pop()?;
+        let undo = self.undos.pop().expect("an undo record for every move");
+        self.position.unmake_move(mv, undo);
+        self.hashes.pop();
+        return Some(mv);
+    }
+
+    // every position of the game, from the starting one to the current one
+    pub fn positions(&self) -> Positions<'_> {
+        return Positions {
+            position: self.starting_position.clone(),
+            moves: self.moves.iter(),
+            done: false,
+        };
+    }
+
+    // how many times the current position has occurred since the last irreversible move
+    pub fn repetition_count(&self) -> usize {
+        return self.position.repetition_count(&self.hashes);
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        return self.position.outcome(&self.hashes);
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        return Game::new(parse_fen(INITIAL_POSITION).expect("the initial position is valid"));
+    }
+}
+
+pub struct Positions<'a> {
+    position: Position,
+    moves: std::slice::Iter<'a, Move>,
+    done: bool,
+}
+
+impl Iterator for Positions<'_> {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        if self.done {
+            return None;
+        }
+
+        let position = self.position.clone();
+        match self.moves.next() {
+            Some(mv) => {
+                self.position.make_move(*mv);
+            }
+            None => self.done = true,
+        }
+        return Some(position);
+    }
+}
@@ -0,0 +1,22 @@
+#![allow(unused)]
+
+pub mod bitboard;
+pub mod chess960;
+pub mod epd;
+pub mod fen_parser;
+pub mod game;
+pub mod magic;
+pub mod move_generator;
+pub mod move_picker;
+pub mod moves;
+pub mod outcome;
+pub mod perft;
+pub mod pext;
+pub mod pgn;
+pub mod piece;
+pub mod position;
+pub mod san;
+pub mod see;
+pub mod uci;
+pub mod validation;
+pub mod zobrist;
@@ -0,0 +1,265 @@
+use lazy_static::lazy_static;
+
+use crate::{bitboard::Bitboard, moves::Square};
+
+/*
+    Rook and bishop attacks using fancy magic bitboards
+    https://www.chessprogramming.org/Magic_Bitboards#Fancy
+
+    The relevant occupancy of a square (its rays without the board edges) is multiplied by
+    the square's magic number and the top bits of the product index that square's slice
+    of a shared attack table. The magic numbers are found offline by the find_magics binary
+*/
+
+pub static ROOK_DIRECTIONS: [(i8, i8); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+pub static BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+// walks every ray square by square, only used to fill the tables
+pub fn sliding_attacks(
+    square: &Square,
+    occupied_squares: &Bitboard,
+    directions: &[(i8, i8)],
+) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    for (file_step, rank_step) in directions {
+        let (mut file, mut rank) = ((square.0 % 8) as i8, (square.0 / 8) as i8);
+        loop {
+            file += file_step;
+            rank += rank_step;
+            if !(0..8).contains(&file) || !(0..8).contains(&rank) {
+                break;
+            }
+            let square_bb = Bitboard(1 << (rank * 8 + file));
+            attacks |= square_bb;
+            if !(occupied_squares & square_bb).is_empty() {
+                break;
+            }
+        }
+    }
+    return attacks;
+}
+
+// squares whose occupancy changes the attacks, the last square of every ray never does
+pub fn relevant_occupancy(square: &Square, directions: &[(i8, i8)]) -> Bitboard {
+    let mut mask = Bitboard::EMPTY;
+    for (file_step, rank_step) in directions {
+        let (mut file, mut rank) = ((square.0 % 8) as i8, (square.0 / 8) as i8);
+        while (0..8).contains(&(file + 2 * file_step)) && (0..8).contains(&(rank + 2 * rank_step)) {
+            file += file_step;
+            rank += rank_step;
+            mask |= Bitboard(1 << (rank * 8 + file));
+        }
+    }
+    return mask;
+}
+
+// every subset of the mask, enumerated with the Carry-Rippler trick
+pub fn occupancy_subsets(mask: &Bitboard) -> Vec<Bitboard> {
+    let mut subsets: Vec<Bitboard> = Vec::with_capacity(1 << mask.count());
+    let mut subset = Bitboard::EMPTY;
+    loop {
+        subsets.push(subset);
+        subset = Bitboard(subset.0.wrapping_sub(mask.0)) & mask;
+        if subset.is_empty() {
+            break;
+        }
+    }
+    return subsets;
+}
+
+struct Magic {
+    mask: Bitboard,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied_squares: &Bitboard) -> usize {
+        return self.offset
+            + ((occupied_squares & self.mask).0.wrapping_mul(self.magic) >> self.shift) as usize;
+    }
+}
+
+struct MagicTable {
+    magics: Vec<Magic>,
+    attacks: Vec<Bitboard>,
+}
+
+impl MagicTable {
+    fn new(magic_numbers: &[u64; 64], directions: &[(i8, i8)]) -> MagicTable {
+        let mut magics: Vec<Magic> = Vec::with_capacity(64);
+        let mut attacks: Vec<Bitboard> = Vec::new();
+
+        for (i, magic_number) in magic_numbers.iter().enumerate() {
+            let square = Square(i as u8);
+            let mask = relevant_occupancy(&square, directions);
+            let magic = Magic {
+                mask,
+                magic: *magic_number,
+                shift: 64 - mask.count(),
+                offset: attacks.len(),
+            };
+
+            attacks.resize(attacks.len() + (1 << mask.count()), Bitboard::EMPTY);
+            for subset in occupancy_subsets(&mask) {
+                attacks[magic.index(&subset)] = sliding_attacks(&square, &subset, directions);
+            }
+            magics.push(magic);
+        }
+
+        return MagicTable { magics, attacks };
+    }
+
+    fn attacks(&self, square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+        return self.attacks[self.magics[square.0 as usize].index(occupied_squares)];
+    }
+}
+
+lazy_static! {
+    static ref ROOK_MAGIC_TABLE: MagicTable = MagicTable::new(&ROOK_MAGICS, &ROOK_DIRECTIONS);
+    static ref BISHOP_MAGIC_TABLE: MagicTable = MagicTable::new(&BISHOP_MAGICS, &BISHOP_DIRECTIONS);
+}
+
+pub fn rook_attacks(square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+    return ROOK_MAGIC_TABLE.attacks(square, occupied_squares);
+}
+
+pub fn bishop_attacks(square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+    return BISHOP_MAGIC_TABLE.attacks(square, occupied_squares);
+}
+
+// generated by `cargo run --release --bin find_magics`
+pub static ROOK_MAGICS: [u64; 64] = [
+    0x0080038040006430,
+    0x0040001000200040,
+    0x2080100020000884,
+    0x0180100081080014,
+    0x8100080002041100,
+    0x4900020804000100,
+    0x0100690012000184,
+    0x40800030800c4300,
+    0x0000800080204000,
+    0x0081004001002080,
+    0x1400802000801008,
+    0x6804801000c80080,
+    0x2002001200040821,
+    0x3000800200800400,
+    0x1000808001000200,
+    0x000a000102004084,
+    0x0088208010804002,
+    0x0000404010002000,
+    0x0010002008002400,
+    0x084a020020081040,
+    0x0010808008000403,
+    0x0011010002080400,
+    0x2080040008108201,
+    0x00401a0000410084,
+    0x1490400080002080,
+    0x0200500240002004,
+    0x0910100080802000,
+    0x2002002200104008,
+    0x0020040080080080,
+    0x040c010040400200,
+    0x0b00810400029008,
+    0xa05000420018a104,
+    0x8180002000400048,
+    0x0940081000200021,
+    0x2000821002802000,
+    0x2000801000800800,
+    0x0008000880800401,
+    0x2101000803000400,
+    0x1340011004000802,
+    0x002b21004a002084,
+    0x6120400080008021,
+    0x0800201000444000,
+    0x0208401082020020,
+    0x0010010080080800,
+    0x8031050801010010,
+    0x2009100420080140,
+    0x1022020001008080,
+    0x806000488102001c,
+    0x0ca0208000400880,
+    0x0490208604411200,
+    0x080e620140108200,
+    0x011a080080100080,
+    0x0058804400280180,
+    0x4000040002008080,
+    0x0441001442002100,
+    0x104001059c004200,
+    0x60c0800020410011,
+    0x020c204100118202,
+    0xc900208040100a02,
+    0x4101002004100209,
+    0x00ae001008042002,
+    0x0281000400020801,
+    0x005210581a030084,
+    0x74c1000180204203,
+];
+
+pub static BISHOP_MAGICS: [u64; 64] = [
+    0x0004080841040010,
+    0xa142304400808001,
+    0x82420c0840848000,
+    0x8004410022201801,
+    0x2004042000300000,
+    0x1088822060001002,
+    0x8c0d089084600000,
+    0x0002004048280880,
+    0x0c09881001420414,
+    0x00200c1908020490,
+    0x82002812164a0480,
+    0x2102080841021804,
+    0x0000020210609000,
+    0x00034088a0080002,
+    0x804ec41101082000,
+    0x0008010851300800,
+    0x0220001184010841,
+    0x0010022004408080,
+    0x08482001060400b0,
+    0xc0c4080124028000,
+    0x400c000880a08400,
+    0x0200408480602010,
+    0x0000802202192010,
+    0x0120400480480888,
+    0x0002100043040800,
+    0x0018488044210800,
+    0x40a4010802080100,
+    0x0002008028008082,
+    0x40a0802002020040,
+    0x0881010182004108,
+    0x4001004052180400,
+    0x00008080060600c0,
+    0x8410224880101008,
+    0x2000ac200444a82d,
+    0x0000489020060400,
+    0x0000020081880080,
+    0x00c0002020060080,
+    0x0288284100009006,
+    0x1004082044822900,
+    0x88f1004100020108,
+    0x020208020a424000,
+    0x0020441004220900,
+    0x009020140a005009,
+    0x8000120122080400,
+    0x040420a008808100,
+    0x8820200d02081840,
+    0x10040800c4140100,
+    0x0070008101100040,
+    0x3306080496580088,
+    0x0102220904200000,
+    0x1200009058080200,
+    0x0804035020880201,
+    0x1000002425041001,
+    0x0000102001c10001,
+    0x00c0650c0401c200,
+    0x0010210104008101,
+    0x20c1420090013000,
+    0x0002092114422002,
+    0x6050881848445000,
+    0x0080400400420200,
+    0xa080000a40308a40,
+    0x6d00202004100090,
+    0x0000502002a40848,
+    0x2408480080820202,
+];
@@ -1,18 +1,34 @@
-#![allow(unused)]
+use std::env;
 
-mod bitboard;
-mod fen_parser;
-mod move_generator;
-mod moves;
-mod piece;
-mod position;
-mod utils;
+use jaingo_unchaind_rust::{bitboard, fen_parser, fen_parser::INITIAL_POSITION, perft};
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    // perft <depth> [fen]: prints the divide of the position, the initial one by default
+    if args.len() > 2 && args[1] == "perft" {
+        let depth = match args[2].parse::<u32>() {
+            Ok(depth) => depth,
+            Err(_) => return println!("invalid depth {}", args[2]),
+        };
+        let fen = match args.len() {
+            3 => INITIAL_POSITION.to_string(),
+            _ => args[3..].join(" "),
+        };
+
+        match fen_parser::parse_fen(&fen) {
+            Ok(mut position) => {
+                perft::print_perft_divide(&mut position, depth);
+            }
+            Err(err) => println!("{}", err),
+        }
+        return;
+    }
+
     match fen_parser::parse_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e4 0 1") {
         Ok(position) => println!("{}", position),
         Err(err) => println!("{}", err),
     }
 
-    println!("{}", bitboard::bitboard_representation(0x8040201008040200));
+    println!("{:?}", bitboard::Bitboard(0x8040201008040200));
 }
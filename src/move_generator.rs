@@ -1,5 +1,119 @@
-use crate::{moves::Move, position::Position};
+use crate::{
+    bitboard::{Bitboard, LINE_THROUGH, SQUARES_BETWEEN},
+    moves::{Move, Square},
+    piece::PieceType,
+    position::{Color, Position},
+};
 
-fn generate_all_moves(position: Position) -> Vec<Move> {
-    unimplemented!()
+// Restrictions every move of the side to move has to satisfy to be legal
+pub struct LegalityMasks {
+    pub king_square: Square,
+    pub checkers: Bitboard,
+    // squares a non king move has to land on: the checking piece and the squares between
+    // it and our king when in single check, nothing when in double check, everything otherwise
+    pub check_mask: Bitboard,
+    pub pinned: Bitboard,
+}
+
+impl LegalityMasks {
+    pub fn new(position: &Position) -> LegalityMasks {
+        let king_square = position.king_square(&position.active_color);
+        let checkers = position.checkers();
+
+        let check_mask = match checkers.count() {
+            0 => Bitboard::FULL,
+            1 => checkers | SQUARES_BETWEEN[king_square.0 as usize][checkers.lsb().0 as usize],
+            _ => Bitboard::EMPTY,
+        };
+
+        return LegalityMasks {
+            king_square,
+            checkers,
+            check_mask,
+            pinned: position.pinned(&position.active_color),
+        };
+    }
+
+    // squares a piece on the given square can move to without exposing our king
+    pub fn pin_mask(&self, square: &Square) -> Bitboard {
+        if !self.pinned.contains(square) {
+            return Bitboard::FULL;
+        }
+        return LINE_THROUGH[self.king_square.0 as usize][square.0 as usize];
+    }
+}
+
+/*
+    Subsets of the legal moves, Captures and Quiets split the legal moves between them
+     - All: every legal move
+     - Captures: captures, en passant and every promotion
+     - Quiets: the remaining moves, castling included
+     - Evasions: every legal move when in check, nothing otherwise
+     - QuietChecks: the quiet moves giving check
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationMode {
+    All,
+    Captures,
+    Quiets,
+    Evasions,
+    QuietChecks,
+}
+
+impl GenerationMode {
+    pub fn includes_captures(&self) -> bool {
+        match self {
+            Self::All | Self::Captures | Self::Evasions => true,
+            Self::Quiets | Self::QuietChecks => false,
+        }
+    }
+
+    pub fn includes_quiets(&self) -> bool {
+        match self {
+            Self::All | Self::Quiets | Self::Evasions | Self::QuietChecks => true,
+            Self::Captures => false,
+        }
+    }
+}
+
+pub fn generate_moves(position: &Position, generation_mode: GenerationMode) -> Vec<Move> {
+    let legality_masks = LegalityMasks::new(position);
+    if generation_mode == GenerationMode::Evasions && legality_masks.checkers.is_empty() {
+        return Vec::new();
+    }
+
+    let mut moves: Vec<Move> = Vec::new();
+    for piece in PieceType::iterator() {
+        // only the king can get out of a double check
+        if legality_masks.check_mask.is_empty() && *piece != PieceType::King {
+            continue;
+        }
+        moves.extend(piece.generate_moves(position, &legality_masks, &generation_mode));
+    }
+
+    if generation_mode == GenerationMode::QuietChecks {
+        moves.retain(|mv| position.gives_check(*mv));
+    }
+    return moves;
+}
+
+pub fn generate_all_moves(position: &Position) -> Vec<Move> {
+    return generate_moves(position, GenerationMode::All);
+}
+
+// whether the move is legal for the side to move, used to validate moves coming from
+// outside the generator such as hash moves and killers
+pub fn is_legal(position: &Position, mv: Move) -> bool {
+    let piece = match position.piece_placement.piece_on(&mv.from()) {
+        Some((color, piece)) if color == position.active_color => piece,
+        _ => return false,
+    };
+
+    let legality_masks = LegalityMasks::new(position);
+    if legality_masks.check_mask.is_empty() && piece != PieceType::King {
+        return false;
+    }
+    return piece
+        .generate_moves(position, &legality_masks, &GenerationMode::All)
+        .contains(&mv);
 }
@@ -0,0 +1,155 @@
+use std::cmp::Reverse;
+
+use crate::{
+    move_generator::{generate_moves, is_legal, GenerationMode},
+    moves::Move,
+    position::Position,
+    see::PieceValues,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    HashMove,
+    GenerateCaptures,
+    GoodCaptures,
+    GenerateQuiets,
+    Killers,
+    Quiets,
+    BadCaptures,
+    Done,
+}
+
+/*
+    Yields the legal moves of a position in the order a search wants to try them
+     1. the hash move
+     2. captures and promotions not losing material, most valuable victim first
+     3. the killer moves
+     4. the remaining quiet moves
+     5. captures losing material
+    Each group is only generated once the previous ones have been consumed, so a cutoff on
+    an early move saves generating the rest. Hash and killer moves are checked for legality
+    and never yielded twice
+*/
+pub struct MovePicker<'a> {
+    position: &'a Position,
+    hash_move: Option<Move>,
+    killers: Vec<Move>,
+    killer_index: usize,
+    stage: Stage,
+    moves: Vec<Move>,
+    index: usize,
+    bad_captures: Vec<Move>,
+}
+
+impl<'a> MovePicker<'a> {
+    pub fn new(
+        position: &'a Position,
+        hash_move: Option<Move>,
+        killers: &[Move],
+    ) -> MovePicker<'a> {
+        return MovePicker {
+            position,
+            hash_move,
+            killers: killers.to_vec(),
+            killer_index: 0,
+            stage: Stage::HashMove,
+            moves: Vec::new(),
+            index: 0,
+            bad_captures: Vec::new(),
+        };
+    }
+
+    fn next_stage_move(&mut self) -> Option<Move> {
+        let mv = self.moves.get(self.index).copied();
+        self.index += 1;
+        return mv;
+    }
+
+    fn load(&mut self, moves: Vec<Move>, stage: Stage) {
+        self.moves = moves;
+        self.index = 0;
+        self.stage = stage;
+    }
+
+    // most valuable victim first, least valuable attacker among equal victims
+    fn mvv_lva(&self, mv: &Move) -> (i32, Reverse<i32>) {
+        let piece_values = PieceValues::default();
+        let piece_placement = &self.position.piece_placement;
+
+        let mut victim_value = match piece_placement.piece_on(&mv.to()) {
+            Some((_, victim)) => piece_values.value(&victim),
+            None if mv.is_en_passant() => piece_values.pawn,
+            None => 0,
+        };
+        if let Some(promotion_piece) = mv.promotion_piece() {
+            victim_value += piece_values.value(&promotion_piece) - piece_values.pawn;
+        }
+        let attacker_value = match piece_placement.piece_on(&mv.from()) {
+            Some((_, attacker)) => piece_values.value(&attacker),
+            None => 0,
+        };
+
+        return (victim_value, Reverse(attacker_value));
+    }
+}
+
+impl Iterator for MovePicker<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::HashMove => {
+                    self.stage = Stage::GenerateCaptures;
+                    match self.hash_move {
+                        Some(hash_move) if is_legal(self.position, hash_move) => {
+                            return Some(hash_move)
+                        }
+                        _ => self.hash_move = None,
+                    }
+                }
+                Stage::GenerateCaptures => {
+                    let mut captures = generate_moves(self.position, GenerationMode::Captures);
+                    captures.retain(|mv| Some(*mv) != self.hash_move);
+                    captures.sort_by_cached_key(|mv| Reverse(self.mvv_lva(mv)));
+                    self.load(captures, Stage::GoodCaptures);
+                }
+                Stage::GoodCaptures => match self.next_stage_move() {
+                    Some(mv) if self.position.see_ge(mv, 0) => return Some(mv),
+                    Some(mv) => self.bad_captures.push(mv),
+                    None => self.stage = Stage::GenerateQuiets,
+                },
+                Stage::GenerateQuiets => {
+                    let mut quiets = generate_moves(self.position, GenerationMode::Quiets);
+                    quiets.retain(|mv| Some(*mv) != self.hash_move);
+                    // killers come from sibling positions and may not be legal here
+                    self.killers.retain(|killer| {
+                        Some(*killer) != self.hash_move && quiets.contains(killer)
+                    });
+                    self.killers.dedup();
+                    quiets.retain(|mv| !self.killers.contains(mv));
+                    self.load(quiets, Stage::Killers);
+                }
+                Stage::Killers => match self.killers.get(self.killer_index) {
+                    Some(killer) => {
+                        self.killer_index += 1;
+                        return Some(*killer);
+                    }
+                    None => self.stage = Stage::Quiets,
+                },
+                Stage::Quiets => match self.next_stage_move() {
+                    Some(mv) => return Some(mv),
+                    None => {
+                        let bad_captures = std::mem::take(&mut self.bad_captures);
+                        self.load(bad_captures, Stage::BadCaptures);
+                    }
+                },
+                Stage::BadCaptures => match self.next_stage_move() {
+                    Some(mv) => return Some(mv),
+                    None => self.stage = Stage::Done,
+                },
+                Stage::Done => return None,
+            }
+        }
+    }
+}
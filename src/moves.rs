@@ -1,6 +1,9 @@
 use core::fmt;
+use std::str::FromStr;
 
-#[derive(Debug)]
+use crate::{piece::PieceType, position::Color};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Square(pub u8);
 
 impl fmt::Display for Square {
@@ -8,10 +11,179 @@ impl fmt::Display for Square {
         write!(
             f,
             "{}{}",
-            (self.0 % 8 + 'a' as u8) as char,
-            (self.0 / 8 + '1' as u8) as char,
+            (self.0 % 8 + b'a') as char,
+            (self.0 / 8 + b'1') as char,
         )
     }
 }
 
-pub type Move = u16;
+impl FromStr for Square {
+    type Err = String;
+
+    fn from_str(square_str: &str) -> Result<Self, Self::Err> {
+        let square_bytes = square_str.as_bytes();
+        if square_bytes.len() != 2
+            || !(b'a'..=b'h').contains(&square_bytes[0])
+            || !(b'1'..=b'8').contains(&square_bytes[1])
+        {
+            return Err(format!("invalid square {}", square_str));
+        }
+
+        return Ok(Square(
+            (square_bytes[1] - b'1') * 8 + square_bytes[0] - b'a',
+        ));
+    }
+}
+
+/*
+    A move packed in 16 bits
+     - bits 0-5: from square
+     - bits 6-11: to square
+     - bits 12-15: flags
+
+    flags   meaning
+    0000    quiet move
+    0001    double pawn push
+    0010    king side castle
+    0011    queen side castle
+    0100    capture
+    0101    en passant capture
+    1000    knight promotion
+    1001    bishop promotion
+    1010    rook promotion
+    1011    queen promotion
+    1100    knight promotion with capture
+    1101    bishop promotion with capture
+    1110    rook promotion with capture
+    1111    queen promotion with capture
+
+    https://www.chessprogramming.org/Encoding_Moves#From-To_Based
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+pub const QUIET_MOVE: u16 = 0;
+pub const DOUBLE_PAWN_PUSH: u16 = 1;
+pub const KING_CASTLE: u16 = 2;
+pub const QUEEN_CASTLE: u16 = 3;
+pub const CAPTURE: u16 = 4;
+pub const EN_PASSANT_CAPTURE: u16 = 5;
+// promotion flags are PROMOTION | piece offset (knight 0, bishop 1, rook 2, queen 3),
+// with CAPTURE set as well for capture promotions
+pub const PROMOTION: u16 = 8;
+
+static PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+];
+
+impl Move {
+    // a1a1 can never be played, so it doubles as the "pass" move
+    pub const NULL: Move = Move(0);
+
+    pub fn new(from: &Square, to: &Square, flags: u16) -> Move {
+        debug_assert!(flags < 16, "move flags {} don't fit in 4 bits", flags);
+        return Move(from.0 as u16 | (to.0 as u16) << 6 | flags << 12);
+    }
+
+    pub fn promotion(from: &Square, to: &Square, piece: &PieceType, is_capture: bool) -> Move {
+        let piece_offset = PROMOTION_PIECES
+            .iter()
+            .position(|promotion_piece| promotion_piece == piece)
+            .expect("pawns can only promote to a knight, bishop, rook or queen")
+            as u16;
+        let capture_flag = if is_capture { CAPTURE } else { QUIET_MOVE };
+
+        return Move::new(from, to, PROMOTION | capture_flag | piece_offset);
+    }
+
+    pub fn from(&self) -> Square {
+        return Square((self.0 & 0x3f) as u8);
+    }
+
+    pub fn to(&self) -> Square {
+        return Square((self.0 >> 6 & 0x3f) as u8);
+    }
+
+    pub fn flags(&self) -> u16 {
+        return self.0 >> 12;
+    }
+
+    pub fn is_null(&self) -> bool {
+        return *self == Move::NULL;
+    }
+
+    pub fn is_capture(&self) -> bool {
+        return self.flags() & CAPTURE != 0;
+    }
+
+    pub fn is_promotion(&self) -> bool {
+        return self.flags() & PROMOTION != 0;
+    }
+
+    pub fn is_en_passant(&self) -> bool {
+        return self.flags() == EN_PASSANT_CAPTURE;
+    }
+
+    pub fn is_double_pawn_push(&self) -> bool {
+        return self.flags() == DOUBLE_PAWN_PUSH;
+    }
+
+    pub fn is_castle(&self) -> bool {
+        return self.flags() == KING_CASTLE || self.flags() == QUEEN_CASTLE;
+    }
+
+    pub fn promotion_piece(&self) -> Option<PieceType> {
+        if !self.is_promotion() {
+            return None;
+        }
+        return Some(PROMOTION_PIECES[(self.flags() & 0b11) as usize]);
+    }
+}
+
+// UCI long algebraic notation: e2e4, e7e8q, 0000 for the null move
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_null() {
+            return f.write_str("0000");
+        }
+
+        write!(f, "{}{}", self.from(), self.to())?;
+        match self.promotion_piece() {
+            Some(piece) => write!(f, "{}", piece.piece_char(&Color::Black)),
+            None => Ok(()),
+        }
+    }
+}
+
+// Without a board the only flags that can be read from the text are the promotion ones,
+// every other move is parsed as a quiet move
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(move_str: &str) -> Result<Self, Self::Err> {
+        if move_str == "0000" {
+            return Ok(Move::NULL);
+        }
+        if !move_str.is_ascii() || !(4..=5).contains(&move_str.len()) {
+            return Err(format!("invalid move {}", move_str));
+        }
+
+        let (from, to) = (
+            move_str[0..2].parse::<Square>()?,
+            move_str[2..4].parse::<Square>()?,
+        );
+
+        match move_str.chars().nth(4) {
+            None => return Ok(Move::new(&from, &to, QUIET_MOVE)),
+            Some(piece_char) => match PieceType::from_char(piece_char) {
+                Some(piece) if PROMOTION_PIECES.contains(&piece) => {
+                    return Ok(Move::promotion(&from, &to, &piece, false))
+                }
+                _ => return Err(format!("invalid promotion piece in move {}", move_str)),
+            },
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use core::fmt;
+
+use crate::{
+    bitboard::Bitboard,
+    move_generator::generate_all_moves,
+    piece::PieceType,
+    position::{Color, Position},
+};
+
+static LIGHT_SQUARES: Bitboard = Bitboard(0x55aa55aa55aa55aa);
+
+/*
+    Ways a game can end. Checkmate, stalemate, insufficient material, the 75 move rule and
+    fivefold repetition end the game on their own, the 50 move rule and threefold repetition
+    only give the player to move the right to claim a draw
+    (FIDE Laws of Chess, articles 5 and 9)
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Checkmate(Color),
+    Stalemate,
+    InsufficientMaterial,
+    SeventyFiveMoveRule,
+    FivefoldRepetition,
+    FiftyMoveRule,
+    ThreefoldRepetition,
+}
+
+impl Outcome {
+    // a claimable draw only ends the game once a player claims it
+    pub fn is_claimable(&self) -> bool {
+        return matches!(self, Self::FiftyMoveRule | Self::ThreefoldRepetition);
+    }
+
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            Self::Checkmate(winner) => Some(*winner),
+            _ => None,
+        }
+    }
+
+    fn string(&self) -> &str {
+        match self {
+            Self::Checkmate(Color::White) => "White wins by checkmate",
+            Self::Checkmate(Color::Black) => "Black wins by checkmate",
+            Self::Stalemate => "Draw by stalemate",
+            Self::InsufficientMaterial => "Draw by insufficient material",
+            Self::SeventyFiveMoveRule => "Draw by the 75 move rule",
+            Self::FivefoldRepetition => "Draw by fivefold repetition",
+            Self::FiftyMoveRule => "Draw claimable by the 50 move rule",
+            Self::ThreefoldRepetition => "Draw claimable by threefold repetition",
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.string())
+    }
+}
+
+impl Position {
+    /*
+        The outcome of the game in this position, None while it goes on. previous_hashes are
+        the keys of the positions before this one in the game, oldest first. Outcomes ending
+        the game take precedence over claimable draws, checkmate over everything else
+    */
+    pub fn outcome(&self, previous_hashes: &[u64]) -> Option<Outcome> {
+        if generate_all_moves(self).is_empty() {
+            if !self.checkers().is_empty() {
+                return Some(Outcome::Checkmate(self.active_color.opponent()));
+            }
+            return Some(Outcome::Stalemate);
+        }
+        if self.has_insufficient_material() {
+            return Some(Outcome::InsufficientMaterial);
+        }
+
+        let repetitions = self.repetition_count(previous_hashes);
+        if self.half_move_clock >= 150 {
+            return Some(Outcome::SeventyFiveMoveRule);
+        }
+        if repetitions >= 5 {
+            return Some(Outcome::FivefoldRepetition);
+        }
+        if self.half_move_clock >= 100 {
+            return Some(Outcome::FiftyMoveRule);
+        }
+        if repetitions >= 3 {
+            return Some(Outcome::ThreefoldRepetition);
+        }
+        return None;
+    }
+
+    // how many times this position has occurred, itself included. Only positions since the
+    // last capture or pawn move, with the same side to move, can be repetitions
+    pub fn repetition_count(&self, previous_hashes: &[u64]) -> usize {
+        let reversible_plies = (self.half_move_clock as usize).min(previous_hashes.len());
+
+        return 1 + previous_hashes[previous_hashes.len() - reversible_plies..]
+            .iter()
+            .rev()
+            .skip(1)
+            .step_by(2)
+            .filter(|hash| **hash == self.hash)
+            .count();
+    }
+
+    /*
+        Neither side can checkmate: bare kings, a single minor piece, or only bishops that
+        all stand on squares of the same color
+    */
+    pub fn has_insufficient_material(&self) -> bool {
+        let piece_placement = &self.piece_placement;
+        let pieces = |piece: &PieceType| {
+            piece_placement.pieces(&Color::White, piece)
+                | piece_placement.pieces(&Color::Black, piece)
+        };
+
+        if !(pieces(&PieceType::Pawn) | pieces(&PieceType::Rook) | pieces(&PieceType::Queen))
+            .is_empty()
+        {
+            return false;
+        }
+
+        let (knights, bishops) = (pieces(&PieceType::Knight), pieces(&PieceType::Bishop));
+        if (knights | bishops).count() <= 1 {
+            return true;
+        }
+        return knights.is_empty()
+            && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & !LIGHT_SQUARES).is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{epd::parse_epd, move_generator::generate_all_moves, moves::Move, position::Position};
+
+/*
+    Counts the leaf nodes of the legal move tree up to the given depth
+    https://www.chessprogramming.org/Perft
+*/
+pub fn perft(position: &mut Position, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = generate_all_moves(position);
+    // bulk counting: the moves of the last ply don't have to be played
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut nodes = 0;
+    for mv in moves {
+        let undo = position.make_move(mv);
+        nodes += perft(position, depth - 1);
+        position.unmake_move(mv, undo);
+    }
+    return nodes;
+}
+
+// node count below every root move, sorted by move
+pub fn perft_divide(position: &mut Position, depth: u32) -> Vec<(Move, u64)> {
+    let mut divide: Vec<(Move, u64)> = Vec::new();
+    if depth == 0 {
+        return divide;
+    }
+
+    for mv in generate_all_moves(position) {
+        let undo = position.make_move(mv);
+        divide.push((mv, perft(position, depth - 1)));
+        position.unmake_move(mv, undo);
+    }
+    divide.sort_by_key(|(mv, _)| mv.to_string());
+    return divide;
+}
+
+// prints the divide in the format most engines use, to diff against their output
+pub fn print_perft_divide(position: &mut Position, depth: u32) -> u64 {
+    let divide = perft_divide(position, depth);
+    for (mv, nodes) in &divide {
+        println!("{}: {}", mv, nodes);
+    }
+
+    let nodes = divide.iter().map(|(_, nodes)| nodes).sum();
+    println!("\nNodes searched: {}", nodes);
+    return nodes;
+}
+
+// a line of a perft suite: a position and the expected node count per depth
+#[derive(Debug)]
+pub struct PerftSuiteEntry {
+    pub position: Position,
+    pub depths: Vec<(u32, u64)>,
+}
+
+/*
+    Parses the perftsuite.epd format, an EPD record per line whose operations are the node
+    counts per depth
+        4k3/8/8/8/8/8/8/4K2R w K - 0 1 ;D1 15 ;D2 66 ;D3 1197
+*/
+pub fn parse_perft_suite_line(line: &str) -> Result<PerftSuiteEntry, String> {
+    let epd = parse_epd(line).map_err(|err| err.to_string())?;
+
+    let mut depths: Vec<(u32, u64)> = Vec::new();
+    for (opcode, operands) in epd.operations() {
+        let field = format!("{} {}", opcode, operands.join(" "));
+        let depth = match opcode.strip_prefix('D') {
+            Some(depth) => depth
+                .parse::<u32>()
+                .map_err(|_| format!("invalid depth in field {}", field))?,
+            None => return Err(format!("invalid perft suite field {}", field)),
+        };
+        let nodes = match operands.as_slice() {
+            [nodes] => nodes
+                .parse::<u64>()
+                .map_err(|_| format!("invalid node count in field {}", field))?,
+            _ => return Err(format!("invalid node count in field {}", field)),
+        };
+        depths.push((depth, nodes));
+    }
+
+    return Ok(PerftSuiteEntry {
+        position: epd.position,
+        depths,
+    });
+}
+
+// empty lines and lines starting with # are skipped
+pub fn parse_perft_suite(perft_suite: &str) -> Result<Vec<PerftSuiteEntry>, String> {
+    return perft_suite
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_perft_suite_line)
+        .collect();
+}
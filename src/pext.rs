@@ -0,0 +1,104 @@
+use lazy_static::lazy_static;
+
+use crate::{
+    bitboard::Bitboard,
+    magic::{
+        occupancy_subsets, relevant_occupancy, sliding_attacks, BISHOP_DIRECTIONS, ROOK_DIRECTIONS,
+    },
+    moves::Square,
+};
+
+/*
+    Rook and bishop attacks indexed with the BMI2 PEXT instruction
+    https://www.chessprogramming.org/BMI2#PEXTBitboards
+
+    PEXT gathers the relevant occupancy bits of a square into a dense index, so unlike the
+    magic tables no multiplication or magic numbers are needed. The instruction is only
+    used when the CPU reports BMI2 support at runtime
+*/
+
+struct PextEntry {
+    mask: Bitboard,
+    offset: usize,
+}
+
+struct PextTable {
+    entries: Vec<PextEntry>,
+    attacks: Vec<Bitboard>,
+}
+
+impl PextTable {
+    fn new(directions: &[(i8, i8)]) -> PextTable {
+        let mut entries: Vec<PextEntry> = Vec::with_capacity(64);
+        let mut attacks: Vec<Bitboard> = Vec::new();
+
+        for i in 0..64 {
+            let square = Square(i);
+            let mask = relevant_occupancy(&square, directions);
+            entries.push(PextEntry {
+                mask,
+                offset: attacks.len(),
+            });
+
+            // the Carry-Rippler enumeration counts through the mask bits in the same
+            // order PEXT packs them, so the n-th subset belongs at index n
+            for subset in occupancy_subsets(&mask) {
+                attacks.push(sliding_attacks(&square, &subset, directions));
+            }
+        }
+
+        return PextTable { entries, attacks };
+    }
+
+    fn attacks(&self, square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+        let entry = &self.entries[square.0 as usize];
+        return self.attacks[entry.offset + pext(occupied_squares.0, entry.mask.0) as usize];
+    }
+}
+
+lazy_static! {
+    static ref BMI2_AVAILABLE: bool = bmi2_detected();
+    static ref ROOK_PEXT_TABLE: PextTable = PextTable::new(&ROOK_DIRECTIONS);
+    static ref BISHOP_PEXT_TABLE: PextTable = PextTable::new(&BISHOP_DIRECTIONS);
+}
+
+#[cfg(target_arch = "x86_64")]
+fn bmi2_detected() -> bool {
+    return is_x86_feature_detected!("bmi2");
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn bmi2_detected() -> bool {
+    return false;
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+unsafe fn pext_bmi2(value: u64, mask: u64) -> u64 {
+    return core::arch::x86_64::_pext_u64(value, mask);
+}
+
+#[cfg(target_arch = "x86_64")]
+fn pext(value: u64, mask: u64) -> u64 {
+    assert!(is_available(), "the CPU does not support BMI2");
+    // SAFETY: BMI2 support was checked at runtime
+    return unsafe { pext_bmi2(value, mask) };
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn pext(value: u64, mask: u64) -> u64 {
+    unreachable!("PEXT is only available on x86-64")
+}
+
+// whether the CPU supports BMI2, the attack functions below panic otherwise
+pub fn is_available() -> bool {
+    return *BMI2_AVAILABLE;
+}
+
+pub fn rook_attacks(square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+    return ROOK_PEXT_TABLE.attacks(square, occupied_squares);
+}
+
+pub fn bishop_attacks(square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+    return BISHOP_PEXT_TABLE.attacks(square, occupied_squares);
+}
@@ -0,0 +1,867 @@
+use core::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+use crate::{
+    fen_parser::{parse_fen, parse_fen_lenient, FenError, INITIAL_POSITION},
+    game::Game,
+    moves::Move,
+    position::{Color, Position},
+    san::SanError,
+};
+
+/*
+    Portable Game Notation
+    https://www.saremba.de/chessgml/standards/pgn/pgn-complete.htm
+
+    A game is read as a tree: the main line is a list of moves and every move may have
+    variations, alternative lines played instead of it from the same position
+*/
+
+pub static SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+// the game termination marker, * for a game still going on or with an unknown result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Unknown,
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WhiteWins => f.write_str("1-0"),
+            Self::BlackWins => f.write_str("0-1"),
+            Self::Draw => f.write_str("1/2-1/2"),
+            Self::Unknown => f.write_str("*"),
+        }
+    }
+}
+
+impl FromStr for GameResult {
+    type Err = String;
+
+    fn from_str(result_str: &str) -> Result<Self, Self::Err> {
+        match result_str {
+            "1-0" => return Ok(Self::WhiteWins),
+            "0-1" => return Ok(Self::BlackWins),
+            "1/2-1/2" => return Ok(Self::Draw),
+            "*" => return Ok(Self::Unknown),
+            _ => return Err(format!("invalid game result {}", result_str)),
+        }
+    }
+}
+
+// a move of a line together with its annotations and the lines played instead of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameNode {
+    pub mv: Move,
+    // comment in front of the move, only written at the start of a variation
+    pub starting_comment: Option<String>,
+    // Numeric Annotation Glyphs, $1 or ! is 1, $2 or ? is 2 and so on
+    pub nags: Vec<u8>,
+    pub comment: Option<String>,
+    pub variations: Vec<Vec<GameNode>>,
+}
+
+impl GameNode {
+    pub fn new(mv: Move) -> GameNode {
+        return GameNode {
+            mv,
+            starting_comment: None,
+            nags: Vec::new(),
+            comment: None,
+            variations: Vec::new(),
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnGame {
+    // tag pairs in the order they were read
+    pub tags: Vec<(String, String)>,
+    // the initial position unless given by the FEN tag
+    pub starting_position: Position,
+    // comment in front of the first move
+    pub comment: Option<String>,
+    pub main_line: Vec<GameNode>,
+    pub result: GameResult,
+}
+
+impl PgnGame {
+    pub fn new(starting_position: Position) -> PgnGame {
+        return PgnGame {
+            tags: Vec::new(),
+            starting_position,
+            comment: None,
+            main_line: Vec::new(),
+            result: GameResult::Unknown,
+        };
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        return self
+            .tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str());
+    }
+
+    // the main line played out as a Game, variations and annotations left out
+    pub fn main_line_game(&self) -> Game {
+        let mut game = Game::new(self.starting_position.clone());
+        for node in &self.main_line {
+            game.push(node.mv).expect("moves of a PgnGame are legal");
+        }
+        return game;
+    }
+}
+
+impl Default for PgnGame {
+    fn default() -> Self {
+        return PgnGame::new(parse_fen(INITIAL_POSITION).expect("the initial position is valid"));
+    }
+}
+
+/*
+    What is wrong with a game, lines are counted from 1 in the whole input. Only the game
+    the error is found in is lost, reading goes on with the next one
+*/
+#[derive(Debug)]
+pub enum PgnError {
+    Io(io::Error),
+    InvalidTag { line: usize },
+    UnexpectedCharacter { line: usize, character: char },
+    InvalidFen { line: usize, error: FenError },
+    InvalidMove { line: usize, error: SanError },
+    // a NAG or variation without a move before it, an unbalanced ')' or a result inside a
+    // variation
+    UnexpectedToken { line: usize, token: String },
+    // a comment or variation still open at the end of the game, line of where it started
+    UnterminatedComment { line: usize },
+    UnterminatedVariation { line: usize },
+}
+
+impl PgnError {
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Io(_) => None,
+            Self::InvalidTag { line }
+            | Self::UnexpectedCharacter { line, .. }
+            | Self::InvalidFen { line, .. }
+            | Self::InvalidMove { line, .. }
+            | Self::UnexpectedToken { line, .. }
+            | Self::UnterminatedComment { line }
+            | Self::UnterminatedVariation { line } => Some(*line),
+        }
+    }
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "reading the PGN failed: {}", error),
+            Self::InvalidTag { line } => write!(f, "line {}: malformed tag pair", line),
+            Self::UnexpectedCharacter { line, character } => {
+                write!(f, "line {}: unexpected character {:?}", line, character)
+            }
+            Self::InvalidFen { line, error } => {
+                write!(f, "line {}: invalid FEN tag, {}", line, error)
+            }
+            Self::InvalidMove { line, error } => write!(f, "line {}: {}", line, error),
+            Self::UnexpectedToken { line, token } => {
+                write!(f, "line {}: unexpected {} in the movetext", line, token)
+            }
+            Self::UnterminatedComment { line } => {
+                write!(
+                    f,
+                    "line {}: comment is not closed before the game ends",
+                    line
+                )
+            }
+            Self::UnterminatedVariation { line } => {
+                write!(
+                    f,
+                    "line {}: variation is not closed before the game ends",
+                    line
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PgnError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::InvalidFen { error, .. } => Some(error),
+            Self::InvalidMove { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Move(String),
+    Nag(u8),
+    Comment(String),
+    VariationStart,
+    VariationEnd,
+    Result(GameResult),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Move(san) => write!(f, "move {}", san),
+            Self::Nag(nag) => write!(f, "NAG ${}", nag),
+            Self::Comment(_) => f.write_str("comment"),
+            Self::VariationStart => f.write_str("'('"),
+            Self::VariationEnd => f.write_str("')'"),
+            Self::Result(result) => write!(f, "result {}", result),
+        }
+    }
+}
+
+// the NAGs of the move suffix annotations
+static SUFFIX_ANNOTATIONS: [(&str, u8); 6] = [
+    ("!", 1),
+    ("?", 2),
+    ("!!", 3),
+    ("??", 4),
+    ("!?", 5),
+    ("?!", 6),
+];
+
+// the tag pairs and movetext tokens of one game, with the line each was found on
+struct GameText {
+    tags: Vec<(usize, String, String)>,
+    tokens: Vec<(usize, Token)>,
+    // an unclosed comment, the line it started on and its text so far
+    open_comment: Option<(usize, String)>,
+    variation_depth: usize,
+    // the first error found, reading continues to the end of the game regardless
+    error: Option<PgnError>,
+}
+
+impl GameText {
+    fn new() -> GameText {
+        return GameText {
+            tags: Vec::new(),
+            tokens: Vec::new(),
+            open_comment: None,
+            variation_depth: 0,
+            error: None,
+        };
+    }
+
+    fn is_empty(&self) -> bool {
+        return self.tags.is_empty() && self.tokens.is_empty() && self.error.is_none();
+    }
+
+    fn record_error(&mut self, error: PgnError) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    // whether the game termination marker was read
+    fn is_complete(&self) -> bool {
+        return self.variation_depth == 0
+            && matches!(self.tokens.last(), Some((_, Token::Result(_))));
+    }
+
+    // [Name "value"] pairs, any number of them on the line
+    fn read_tags(&mut self, line_number: usize, line: &str) {
+        let mut rest = line.trim();
+        while !rest.is_empty() {
+            match parse_tag(rest) {
+                Some((name, value, after_tag)) => {
+                    self.tags.push((line_number, name, value));
+                    rest = after_tag.trim_start();
+                }
+                None => {
+                    self.record_error(PgnError::InvalidTag { line: line_number });
+                    return;
+                }
+            }
+        }
+    }
+
+    fn read_movetext(&mut self, line_number: usize, line: &str) {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if let Some((_, text)) = &mut self.open_comment {
+                match chars[i..].iter().position(|c| *c == '}') {
+                    Some(end) => {
+                        text.extend(&chars[i..i + end]);
+                        let (_, text) = self.open_comment.take().unwrap();
+                        self.push(line_number, Token::Comment(text.trim().to_string()));
+                        i += end + 1;
+                    }
+                    None => {
+                        text.extend(&chars[i..]);
+                        // writers wrap long comments, the line break stands for a space
+                        text.push(' ');
+                        return;
+                    }
+                }
+                continue;
+            }
+
+            let c = chars[i];
+            let symbol_end = |start: usize| -> usize {
+                (start..chars.len())
+                    .find(|j| !is_symbol_char(chars[*j]))
+                    .unwrap_or(chars.len())
+            };
+            match c {
+                '{' => {
+                    self.open_comment = Some((line_number, String::new()));
+                    i += 1;
+                }
+                ';' => {
+                    let text: String = chars[i + 1..].iter().collect();
+                    self.push(line_number, Token::Comment(text.trim().to_string()));
+                    return;
+                }
+                '(' => {
+                    self.variation_depth += 1;
+                    self.push(line_number, Token::VariationStart);
+                    i += 1;
+                }
+                ')' => {
+                    self.variation_depth = self.variation_depth.saturating_sub(1);
+                    self.push(line_number, Token::VariationEnd);
+                    i += 1;
+                }
+                '*' => {
+                    self.push(line_number, Token::Result(GameResult::Unknown));
+                    i += 1;
+                }
+                '$' => {
+                    let end = (i + 1..chars.len())
+                        .find(|j| !chars[*j].is_ascii_digit())
+                        .unwrap_or(chars.len());
+                    let digits: String = chars[i + 1..end].iter().collect();
+                    match digits.parse::<u8>() {
+                        Ok(nag) => self.push(line_number, Token::Nag(nag)),
+                        Err(_) => self.record_error(PgnError::UnexpectedCharacter {
+                            line: line_number,
+                            character: '$',
+                        }),
+                    }
+                    i = end;
+                }
+                '!' | '?' => {
+                    let end = (i..chars.len())
+                        .find(|j| !matches!(chars[*j], '!' | '?'))
+                        .unwrap_or(chars.len());
+                    let annotation: String = chars[i..end].iter().collect();
+                    match SUFFIX_ANNOTATIONS
+                        .iter()
+                        .find(|(glyph, _)| *glyph == annotation)
+                    {
+                        Some((_, nag)) => self.push(line_number, Token::Nag(*nag)),
+                        None => self.record_error(PgnError::UnexpectedCharacter {
+                            line: line_number,
+                            character: c,
+                        }),
+                    }
+                    i = end;
+                }
+                _ if c.is_whitespace() => i += 1,
+                _ if is_symbol_char(c) => {
+                    let end = symbol_end(i);
+                    let symbol: String = chars[i..end].iter().collect();
+                    self.read_symbol(line_number, &symbol);
+                    i = end;
+                }
+                _ => {
+                    self.record_error(PgnError::UnexpectedCharacter {
+                        line: line_number,
+                        character: c,
+                    });
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    // a result, a move number indication like 12. or 12... or a move, possibly glued
+    // together as in 12.e4
+    fn read_symbol(&mut self, line_number: usize, symbol: &str) {
+        if let Ok(result) = symbol.parse::<GameResult>() {
+            self.push(line_number, Token::Result(result));
+            return;
+        }
+
+        let after_number = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+        let move_text = match after_number.strip_prefix('.') {
+            Some(after_dot) => after_dot.trim_start_matches('.'),
+            None if after_number.is_empty() => "",
+            None => symbol,
+        };
+        if !move_text.is_empty() {
+            self.push(line_number, Token::Move(move_text.to_string()));
+        }
+    }
+
+    fn push(&mut self, line_number: usize, token: Token) {
+        self.tokens.push((line_number, token));
+    }
+}
+
+// characters of moves, move numbers and results
+fn is_symbol_char(c: char) -> bool {
+    return c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '#' | '=' | ':' | '-' | '/' | '.');
+}
+
+// the name and value of the tag pair the text starts with, and the text after it
+fn parse_tag(text: &str) -> Option<(String, String, &str)> {
+    let rest = text.strip_prefix('[')?.trim_start();
+    let name_length = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    if name_length == 0 {
+        return None;
+    }
+    let name = rest[..name_length].to_string();
+    let rest = rest[name_length..].trim_start().strip_prefix('"')?;
+
+    // \" and \\ are the only escapes
+    let mut value = String::new();
+    let mut chars = rest.char_indices();
+    let value_end = loop {
+        match chars.next()? {
+            (_, '\\') => value.push(chars.next()?.1),
+            (index, '"') => break index,
+            (_, c) => value.push(c),
+        }
+    };
+    let rest = rest[value_end + 1..].trim_start().strip_prefix(']')?;
+    return Some((name, value, rest));
+}
+
+/*
+    Reads the games of a PGN one at a time, so files of any size can be streamed. Every
+    game is either a PgnGame or the first error found in it
+*/
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    line_number: usize,
+    // the first line of the next game, read while looking for the end of the last one
+    pending_line: Option<(usize, String)>,
+    failed: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> PgnReader<R> {
+        return PgnReader {
+            reader,
+            line_number: 0,
+            pending_line: None,
+            failed: false,
+        };
+    }
+
+    fn next_line(&mut self) -> io::Result<Option<(usize, String)>> {
+        if let Some(line) = self.pending_line.take() {
+            return Ok(Some(line));
+        }
+
+        let mut bytes = Vec::new();
+        if self.reader.read_until(b'\n', &mut bytes)? == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+        let line = String::from_utf8_lossy(&bytes);
+        let line = line.trim_end_matches(['\n', '\r']);
+        let line = match self.line_number {
+            1 => line.trim_start_matches('\u{feff}'),
+            _ => line,
+        };
+        return Ok(Some((self.line_number, line.to_string())));
+    }
+
+    // the text of the next game, which ends with its result, the tags of the next game
+    // or the end of the input
+    fn read_game_text(&mut self) -> io::Result<GameText> {
+        let mut game_text = GameText::new();
+        while let Some((line_number, line)) = self.next_line()? {
+            if game_text.open_comment.is_none() {
+                let trimmed = line.trim_start();
+                // escape mechanism, the line is skipped
+                if line.starts_with('%') {
+                    continue;
+                }
+                if trimmed.starts_with('[') {
+                    if !game_text.tokens.is_empty() {
+                        self.pending_line = Some((line_number, line));
+                        break;
+                    }
+                    game_text.read_tags(line_number, trimmed);
+                    continue;
+                }
+            }
+
+            game_text.read_movetext(line_number, &line);
+            if game_text.is_complete() {
+                break;
+            }
+        }
+        return Ok(game_text);
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let game_text = match self.read_game_text() {
+            Ok(game_text) => game_text,
+            Err(error) => {
+                self.failed = true;
+                return Some(Err(PgnError::Io(error)));
+            }
+        };
+        if game_text.is_empty() && game_text.open_comment.is_none() {
+            return None;
+        }
+        return Some(build_game(game_text));
+    }
+}
+
+fn build_game(mut game_text: GameText) -> Result<PgnGame, PgnError> {
+    if let Some(error) = game_text.error.take() {
+        return Err(error);
+    }
+    if let Some((line, _)) = game_text.open_comment {
+        return Err(PgnError::UnterminatedComment { line });
+    }
+
+    let mut game = PgnGame::default();
+    for (line, name, value) in game_text.tags {
+        // web exports often leave the move clocks out of the tag
+        if name == "FEN" {
+            game.starting_position =
+                parse_fen_lenient(&value).map_err(|error| PgnError::InvalidFen { line, error })?;
+        }
+        game.tags.push((name, value));
+    }
+
+    let mut line_builder = LineBuilder {
+        tokens: &game_text.tokens,
+        index: 0,
+        result: None,
+    };
+    let (comment, main_line) = line_builder.build_line(game.starting_position.clone(), None)?;
+    game.comment = comment;
+    game.main_line = main_line;
+    game.result = match line_builder.result {
+        Some(result) => result,
+        None => game
+            .tag("Result")
+            .and_then(|result| result.parse::<GameResult>().ok())
+            .unwrap_or(GameResult::Unknown),
+    };
+    return Ok(game);
+}
+
+// turns the movetext tokens into lines, replaying the moves to read the SAN
+struct LineBuilder<'a> {
+    tokens: &'a [(usize, Token)],
+    index: usize,
+    result: Option<GameResult>,
+}
+
+impl LineBuilder<'_> {
+    /*
+        The comment in front of the line and its moves. variation_start is the line of the
+        '(' for a variation and None for the main line, which ends with the result or the
+        last token
+    */
+    fn build_line(
+        &mut self,
+        mut position: Position,
+        variation_start: Option<usize>,
+    ) -> Result<(Option<String>, Vec<GameNode>), PgnError> {
+        let mut comment: Option<String> = None;
+        let mut nodes: Vec<GameNode> = Vec::new();
+        // the position before the last move, where its variations start from
+        let mut previous_position: Option<Position> = None;
+
+        while let Some((line, token)) = self.tokens.get(self.index) {
+            let (line, token) = (*line, token);
+            self.index += 1;
+            let unexpected_token = || PgnError::UnexpectedToken {
+                line,
+                token: token.to_string(),
+            };
+
+            match token {
+                Token::Move(san) => {
+                    let mv = position
+                        .parse_san(san)
+                        .map_err(|error| PgnError::InvalidMove { line, error })?;
+                    previous_position = Some(position.clone());
+                    position.make_move(mv);
+                    nodes.push(GameNode::new(mv));
+                }
+                Token::Nag(nag) => match nodes.last_mut() {
+                    Some(node) => node.nags.push(*nag),
+                    None => return Err(unexpected_token()),
+                },
+                Token::Comment(text) => match nodes.last_mut() {
+                    Some(node) => append_comment(&mut node.comment, text),
+                    None => append_comment(&mut comment, text),
+                },
+                Token::VariationStart => {
+                    let variation_position =
+                        previous_position.clone().ok_or_else(unexpected_token)?;
+                    let (starting_comment, mut variation) =
+                        self.build_line(variation_position, Some(line))?;
+                    if let Some(first_node) = variation.first_mut() {
+                        first_node.starting_comment = starting_comment;
+                        nodes.last_mut().unwrap().variations.push(variation);
+                    }
+                }
+                Token::VariationEnd => match variation_start {
+                    Some(_) => return Ok((comment, nodes)),
+                    None => return Err(unexpected_token()),
+                },
+                Token::Result(result) => match variation_start {
+                    Some(_) => return Err(unexpected_token()),
+                    None => {
+                        self.result = Some(*result);
+                        return Ok((comment, nodes));
+                    }
+                },
+            }
+        }
+
+        match variation_start {
+            Some(line) => return Err(PgnError::UnterminatedVariation { line }),
+            None => return Ok((comment, nodes)),
+        }
+    }
+}
+
+// comments following each other are joined into one
+fn append_comment(comment: &mut Option<String>, text: &str) {
+    match comment {
+        Some(comment) => {
+            comment.push(' ');
+            comment.push_str(text);
+        }
+        None => *comment = Some(text.to_string()),
+    }
+}
+
+// movetext lines are wrapped to this many characters, tag pairs are never split
+static LINE_WIDTH: usize = 80;
+
+// what to_pgn writes besides the tags and moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgnWriteOptions {
+    pub comments: bool,
+    pub variations: bool,
+}
+
+impl Default for PgnWriteOptions {
+    fn default() -> Self {
+        return PgnWriteOptions {
+            comments: true,
+            variations: true,
+        };
+    }
+}
+
+impl PgnGame {
+    /*
+        The game in PGN export format: the seven tag roster in its order, "?" for missing
+        tags, SetUp and FEN when the game starts from another position than the initial one,
+        the other tags as they are, then the movetext wrapped at 80 columns
+    */
+    pub fn to_pgn(&self, options: &PgnWriteOptions) -> String {
+        let mut pgn = String::new();
+        for (name, value) in self.export_tags() {
+            pgn += &format!("[{} \"{}\"]\n", name, escape_tag_value(&value));
+        }
+        pgn.push('\n');
+
+        let mut tokens: Vec<String> = Vec::new();
+        if options.comments {
+            if let Some(comment) = &self.comment {
+                push_comment(&mut tokens, comment);
+            }
+        }
+        write_line(
+            &mut tokens,
+            self.starting_position.clone(),
+            &self.main_line,
+            options,
+        );
+        tokens.push(self.result.to_string());
+
+        for line in wrap_tokens(&tokens) {
+            pgn += &line;
+            pgn.push('\n');
+        }
+        return pgn;
+    }
+
+    fn export_tags(&self) -> Vec<(String, String)> {
+        let mut tags: Vec<(String, String)> = SEVEN_TAG_ROSTER
+            .iter()
+            .map(|name| {
+                let value = match *name {
+                    "Result" => self.result.to_string(),
+                    "Date" => self.tag(name).unwrap_or("????.??.??").to_string(),
+                    _ => self.tag(name).unwrap_or("?").to_string(),
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+
+        let initial_position = parse_fen(INITIAL_POSITION).expect("the initial position is valid");
+        if self.starting_position != initial_position {
+            tags.push((String::from("SetUp"), String::from("1")));
+            tags.push((String::from("FEN"), self.starting_position.to_fen()));
+        }
+
+        tags.extend(
+            self.tags
+                .iter()
+                .filter(|(name, _)| {
+                    !SEVEN_TAG_ROSTER.contains(&name.as_str()) && name != "SetUp" && name != "FEN"
+                })
+                .cloned(),
+        );
+        return tags;
+    }
+}
+
+// the PGN with the default options
+impl fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_pgn(&PgnWriteOptions::default()))
+    }
+}
+
+fn escape_tag_value(value: &str) -> String {
+    return value.replace('\\', "\\\\").replace('"', "\\\"");
+}
+
+/*
+    A comment split into words so long comments can be wrapped. PGN has no escape for a
+    closing brace inside a brace comment, so those are left out
+*/
+fn push_comment(tokens: &mut Vec<String>, comment: &str) {
+    let comment = comment.replace('}', "");
+    let words: Vec<&str> = comment.split_whitespace().collect();
+    match words.len() {
+        0 => tokens.push(String::from("{}")),
+        1 => tokens.push(format!("{{{}}}", words[0])),
+        _ => {
+            tokens.push(format!("{{{}", words[0]));
+            tokens.extend(
+                words[1..words.len() - 1]
+                    .iter()
+                    .map(|word| word.to_string()),
+            );
+            tokens.push(format!("{}}}", words[words.len() - 1]));
+        }
+    }
+}
+
+/*
+    The tokens of a line played from the position. White moves always get their move
+    number, black moves only at the start of a line and after a comment or variations
+*/
+fn write_line(
+    tokens: &mut Vec<String>,
+    mut position: Position,
+    nodes: &[GameNode],
+    options: &PgnWriteOptions,
+) {
+    let mut needs_move_number = true;
+    for node in nodes {
+        if options.comments {
+            if let Some(comment) = &node.starting_comment {
+                push_comment(tokens, comment);
+                needs_move_number = true;
+            }
+        }
+
+        match position.active_color {
+            Color::White => tokens.push(format!("{}.", position.full_move_number)),
+            Color::Black if needs_move_number => {
+                tokens.push(format!("{}...", position.full_move_number))
+            }
+            Color::Black => {}
+        }
+        tokens.push(position.to_san(node.mv));
+        tokens.extend(node.nags.iter().map(|nag| format!("${}", nag)));
+        needs_move_number = false;
+
+        if options.comments {
+            if let Some(comment) = &node.comment {
+                push_comment(tokens, comment);
+                needs_move_number = true;
+            }
+        }
+
+        if options.variations {
+            for variation in node
+                .variations
+                .iter()
+                .filter(|variation| !variation.is_empty())
+            {
+                // the parentheses stick to the first and last token of the variation
+                let mut variation_tokens: Vec<String> = Vec::new();
+                write_line(&mut variation_tokens, position.clone(), variation, options);
+                variation_tokens[0].insert(0, '(');
+                variation_tokens.last_mut().unwrap().push(')');
+                tokens.extend(variation_tokens);
+                needs_move_number = true;
+            }
+        }
+
+        position.make_move(node.mv);
+    }
+}
+
+// tokens joined by spaces into lines of at most LINE_WIDTH characters
+fn wrap_tokens(tokens: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for token in tokens {
+        let token_width = token.chars().count();
+        if line_width > 0 && line_width + 1 + token_width > LINE_WIDTH {
+            lines.push(line);
+            line = String::new();
+            line_width = 0;
+        } else if line_width > 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+        line += token;
+        line_width += token_width;
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    return lines;
+}
@@ -1,5 +1,16 @@
-use crate::bitboard::{Bitboard, KING_ATTACKS, KNIGHT_ATTACKS, PAWN_ATTACKS};
-use crate::moves::{Move, Square};
+use crate::bitboard::{
+    Bitboard, KING_ATTACKS, KNIGHT_ATTACKS, PAWN_ATTACKS, RANK_1, RANK_2, RANK_7, RANK_8,
+    SQUARES_BETWEEN,
+};
+#[cfg(feature = "magic")]
+use crate::magic;
+use crate::move_generator::{GenerationMode, LegalityMasks};
+use crate::moves::{
+    Move, Square, CAPTURE, DOUBLE_PAWN_PUSH, EN_PASSANT_CAPTURE, KING_CASTLE, QUEEN_CASTLE,
+    QUIET_MOVE,
+};
+#[cfg(feature = "pext")]
+use crate::pext;
 use crate::position::{Color, Position};
 use core::fmt;
 use std::char;
@@ -58,6 +69,34 @@ impl PieceType {
         }
     }
 
+    // letter used by FEN and algebraic notation, upper case for white
+    pub fn piece_char(&self, color: &Color) -> char {
+        let piece_char = match self {
+            PieceType::Pawn => 'p',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::Rook => 'r',
+            PieceType::Queen => 'q',
+            PieceType::King => 'k',
+        };
+        match color {
+            Color::White => piece_char.to_ascii_uppercase(),
+            Color::Black => piece_char,
+        }
+    }
+
+    pub fn from_char(piece_char: char) -> Option<PieceType> {
+        match piece_char.to_ascii_lowercase() {
+            'p' => Some(PieceType::Pawn),
+            'n' => Some(PieceType::Knight),
+            'b' => Some(PieceType::Bishop),
+            'r' => Some(PieceType::Rook),
+            'q' => Some(PieceType::Queen),
+            'k' => Some(PieceType::King),
+            _ => None,
+        }
+    }
+
     pub fn is_slider(&self) -> bool {
         match self {
             Self::Pawn | Self::Knight | Self::King => false,
@@ -77,8 +116,67 @@ impl PieceType {
         PIECETYPES.iter()
     }
 
-    pub fn generate_moves(position: Position) -> Vec<Move> {
-        unimplemented!()
+    // legal moves of the side to move for this piece type, restricted to the given mode
+    pub fn generate_moves(
+        &self,
+        position: &Position,
+        legality_masks: &LegalityMasks,
+        generation_mode: &GenerationMode,
+    ) -> Vec<Move> {
+        let us = position.active_color;
+        let piece_placement = &position.piece_placement;
+        let (our_squares, opponent_squares) = (
+            piece_placement.occupancy(&us),
+            piece_placement.occupancy(&us.opponent()),
+        );
+        let target_squares = match (
+            generation_mode.includes_captures(),
+            generation_mode.includes_quiets(),
+        ) {
+            (true, true) => !our_squares,
+            (true, false) => opponent_squares,
+            (false, true) => !(our_squares | opponent_squares),
+            (false, false) => Bitboard::EMPTY,
+        };
+
+        let mut moves: Vec<Move> = Vec::new();
+        for from in piece_placement.pieces(&us, self) {
+            match self {
+                Self::Pawn => generate_pawn_moves(
+                    position,
+                    legality_masks,
+                    generation_mode,
+                    &from,
+                    &mut moves,
+                ),
+                Self::King => {
+                    let targets = KING_ATTACKS[from.0 as usize] & target_squares;
+                    // the king must not shield the squares behind it from a slider
+                    let occupied_squares =
+                        (our_squares | opponent_squares) & !Bitboard::from_square(&from);
+                    for to in targets {
+                        if (position.attackers_to(&to, &occupied_squares) & opponent_squares)
+                            .is_empty()
+                        {
+                            moves.push(Move::new(&from, &to, capture_flag(&to, &opponent_squares)));
+                        }
+                    }
+                    if generation_mode.includes_quiets() {
+                        generate_castling_moves(position, legality_masks, &from, &mut moves);
+                    }
+                }
+                _ => {
+                    let targets = self.attact_bitboard(&us, &from, &our_squares, &opponent_squares)
+                        & target_squares
+                        & legality_masks.check_mask
+                        & legality_masks.pin_mask(&from);
+                    for to in targets {
+                        moves.push(Move::new(&from, &to, capture_flag(&to, &opponent_squares)));
+                    }
+                }
+            }
+        }
+        return moves;
     }
 
     pub fn attact_bitboard(
@@ -93,26 +191,215 @@ impl PieceType {
         match self {
             Self::King => KING_ATTACKS[square.0 as usize],
             Self::Queen => {
-                Slider::Diagonal.slider_attacks(&square, &all_occupied)
-                    | Slider::AntiDiagonal.slider_attacks(&square, &all_occupied)
-                    | Slider::File.slider_attacks(&square, &all_occupied)
-                    | Slider::Rank.slider_attacks(&square, &all_occupied)
-            }
-            Self::Rook => {
-                Slider::File.slider_attacks(&square, &all_occupied)
-                    | Slider::Rank.slider_attacks(&square, &all_occupied)
-            }
-            Self::Bishop => {
-                Slider::Diagonal.slider_attacks(&square, &all_occupied)
-                    | Slider::AntiDiagonal.slider_attacks(&square, &all_occupied)
+                rook_attacks(square, &all_occupied) | bishop_attacks(square, &all_occupied)
             }
+            Self::Rook => rook_attacks(square, &all_occupied),
+            Self::Bishop => bishop_attacks(square, &all_occupied),
             Self::Knight => KNIGHT_ATTACKS[square.0 as usize],
-            Self::Pawn => PAWN_ATTACKS.get(&color).unwrap()[square.0 as usize],
+            Self::Pawn => PAWN_ATTACKS.get(color).unwrap()[square.0 as usize],
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+fn capture_flag(to: &Square, opponent_squares: &Bitboard) -> u16 {
+    if opponent_squares.contains(to) {
+        return CAPTURE;
+    }
+    return QUIET_MOVE;
+}
+
+fn push_promotions(from: &Square, to: &Square, is_capture: bool, moves: &mut Vec<Move>) {
+    for piece in [
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+    ] {
+        moves.push(Move::promotion(from, to, &piece, is_capture));
+    }
+}
+
+// promotions are generated along with the captures
+fn generate_pawn_moves(
+    position: &Position,
+    legality_masks: &LegalityMasks,
+    generation_mode: &GenerationMode,
+    from: &Square,
+    moves: &mut Vec<Move>,
+) {
+    let us = position.active_color;
+    let piece_placement = &position.piece_placement;
+    let opponent_squares = piece_placement.occupancy(&us.opponent());
+    let occupied_squares = piece_placement.all_occupancy();
+    let (push_offset, double_push_rank, promotion_rank): (i8, Bitboard, Bitboard) = match us {
+        Color::White => (8, RANK_2, RANK_8),
+        Color::Black => (-8, RANK_7, RANK_1),
+    };
+    let legal_squares = legality_masks.check_mask & legality_masks.pin_mask(from);
+
+    let (captures_included, quiets_included) = (
+        generation_mode.includes_captures(),
+        generation_mode.includes_quiets(),
+    );
+
+    let push = Square((from.0 as i8 + push_offset) as u8);
+    if !occupied_squares.contains(&push) {
+        if legal_squares.contains(&push) {
+            if promotion_rank.contains(&push) {
+                if captures_included {
+                    push_promotions(from, &push, false, moves);
+                }
+            } else if quiets_included {
+                moves.push(Move::new(from, &push, QUIET_MOVE));
+            }
+        }
+
+        let double_push = Square((push.0 as i8 + push_offset) as u8);
+        if quiets_included
+            && double_push_rank.contains(from)
+            && !occupied_squares.contains(&double_push)
+            && legal_squares.contains(&double_push)
+        {
+            moves.push(Move::new(from, &double_push, DOUBLE_PAWN_PUSH));
+        }
+    }
+
+    if !captures_included {
+        return;
+    }
+
+    let captures =
+        PAWN_ATTACKS.get(&us).unwrap()[from.0 as usize] & opponent_squares & legal_squares;
+    for to in captures {
+        if promotion_rank.contains(&to) {
+            push_promotions(from, &to, true, moves);
+        } else {
+            moves.push(Move::new(from, &to, CAPTURE));
+        }
+    }
+
+    if let Some(en_passant_target) = position.en_passant_target {
+        if !PAWN_ATTACKS.get(&us).unwrap()[from.0 as usize].contains(&en_passant_target) {
+            return;
+        }
+
+        // en passant removes two pieces from the same rank, so the resulting position is
+        // checked as a whole instead of relying on the pin and check masks
+        let captured = Square((en_passant_target.0 as i8 - push_offset) as u8);
+        let occupied_after_capture =
+            (occupied_squares & !Bitboard::from_square(from) & !Bitboard::from_square(&captured))
+                | Bitboard::from_square(&en_passant_target);
+        if (position.attackers_to(&legality_masks.king_square, &occupied_after_capture)
+            & opponent_squares)
+            .is_empty()
+        {
+            moves.push(Move::new(from, &en_passant_target, EN_PASSANT_CAPTURE));
+        }
+    }
+}
+
+/*
+    Castling by the Chess960 rules, the standard ones being a special case: the king lands on
+    the g file (king side) or c file (queen side) and the rook next to it on the f or d file.
+    Every square either of them crosses or lands on has to be empty apart from the two of them,
+    and no square the king crosses or lands on may be attacked
+*/
+fn generate_castling_moves(
+    position: &Position,
+    legality_masks: &LegalityMasks,
+    king_square: &Square,
+    moves: &mut Vec<Move>,
+) {
+    let us = position.active_color;
+    let castling_rights = match &position.castling_rights {
+        Some(castling_rights) => castling_rights,
+        None => return,
+    };
+    if !legality_masks.checkers.is_empty() {
+        return;
+    }
+
+    let home_rank_offset = us.home_rank_offset();
+    if king_square.0 / 8 != home_rank_offset / 8 {
+        return;
+    }
+
+    let occupied_squares = position.piece_placement.all_occupancy();
+    let opponent_squares = position.piece_placement.occupancy(&us.opponent());
+    let rooks = position.piece_placement.pieces(&us, &PieceType::Rook);
+    // (rook square, king destination file, rook destination file, flag)
+    let castlings = [
+        (castling_rights.king_side_rook(&us), 6, 5, KING_CASTLE),
+        (castling_rights.queen_side_rook(&us), 2, 3, QUEEN_CASTLE),
+    ];
+
+    for (rook_square, king_to_file, rook_to_file, flag) in castlings {
+        let rook_square = match rook_square {
+            Some(rook_square) if rooks.contains(&rook_square) => rook_square,
+            _ => continue,
+        };
+        let (king_to, rook_to) = (
+            home_rank_offset + king_to_file,
+            home_rank_offset + rook_to_file,
+        );
+
+        let king_path = SQUARES_BETWEEN[king_square.0 as usize][king_to as usize]
+            | Bitboard::from_square(&Square(king_to));
+        let rook_path = SQUARES_BETWEEN[rook_square.0 as usize][rook_to as usize]
+            | Bitboard::from_square(&Square(rook_to));
+        let castling_pieces =
+            Bitboard::from_square(king_square) | Bitboard::from_square(&rook_square);
+        if !(occupied_squares & (king_path | rook_path) & !castling_pieces).is_empty() {
+            continue;
+        }
+
+        // the rook may have been shielding the king path from a slider on the home rank
+        let occupied_without_rook = occupied_squares & !Bitboard::from_square(&rook_square);
+        let attacked = king_path.into_iter().any(|square| {
+            !(position.attackers_to(&square, &occupied_without_rook) & opponent_squares).is_empty()
+        });
+        if !attacked {
+            moves.push(Move::new(king_square, &Square(king_to), flag));
+        }
+    }
+}
+
+/*
+    Slider attack backends
+     - pext feature: PEXT indexed tables when the CPU supports BMI2, falling back to the
+       portable backends below otherwise
+     - magic feature: fancy magic bitboards
+     - default: Hyperbola Quintessence
+*/
+fn rook_attacks(square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+    #[cfg(feature = "pext")]
+    if pext::is_available() {
+        return pext::rook_attacks(square, occupied_squares);
+    }
+
+    #[cfg(feature = "magic")]
+    return magic::rook_attacks(square, occupied_squares);
+
+    #[cfg(not(feature = "magic"))]
+    return Slider::File.slider_attacks(square, occupied_squares)
+        | Slider::Rank.slider_attacks(square, occupied_squares);
+}
+
+fn bishop_attacks(square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+    #[cfg(feature = "pext")]
+    if pext::is_available() {
+        return pext::bishop_attacks(square, occupied_squares);
+    }
+
+    #[cfg(feature = "magic")]
+    return magic::bishop_attacks(square, occupied_squares);
+
+    #[cfg(not(feature = "magic"))]
+    return Slider::Diagonal.slider_attacks(square, occupied_squares)
+        | Slider::AntiDiagonal.slider_attacks(square, occupied_squares);
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum Slider {
     File,
     Rank,
@@ -126,34 +413,98 @@ struct SquareMask {
 }
 
 lazy_static! {
-    static ref SQUARE_MASKS: [SquareMask; 64] = { unimplemented!() };
+    static ref SQUARE_MASKS: [SquareMask; 64] = {
+        core::array::from_fn(|i| {
+            let (file, rank) = ((i % 8) as i32, (i / 8) as i32);
+            let bit_mask = Bitboard(1 << i);
+
+            // every square sharing the line with square i, the square itself excluded
+            let line_mask = |on_line: &dyn Fn(i32, i32) -> bool| -> Bitboard {
+                (0..64)
+                    .filter(|&j| on_line(j % 8, j / 8))
+                    .fold(Bitboard::EMPTY, |mask, j| mask | Bitboard(1 << j))
+                    & !bit_mask
+            };
+
+            let mut slider_mask_ex = HashMap::new();
+            slider_mask_ex.insert(Slider::File, line_mask(&|f, _| f == file));
+            slider_mask_ex.insert(Slider::Rank, line_mask(&|_, r| r == rank));
+            slider_mask_ex.insert(Slider::Diagonal, line_mask(&|f, r| r - f == rank - file));
+            slider_mask_ex.insert(Slider::AntiDiagonal, line_mask(&|f, r| r + f == rank + file));
+
+            SquareMask {
+                bit_mask,
+                slider_mask_ex,
+            }
+        })
+    };
+    /*
+        Rank attacks of a rook on the first rank, indexed by the file of the rook and the
+        occupancy of the six inner squares (the edge squares are always attacked if reached)
+        https://www.chessprogramming.org/First_Rank_Attacks
+    */
+    static ref FIRST_RANK_ATTACKS: [[u8; 64]; 8] = {
+        let mut first_rank_attacks = [[0; 64]; 8];
+
+        for (file, attacks) in first_rank_attacks.iter_mut().enumerate() {
+            for (inner_occupancy, attack) in attacks.iter_mut().enumerate() {
+                let occupancy = (inner_occupancy as u8) << 1;
+
+                for to in (file + 1)..8 {
+                    *attack |= 1 << to;
+                    if occupancy & (1 << to) != 0 {
+                        break;
+                    }
+                }
+                for to in (0..file).rev() {
+                    *attack |= 1 << to;
+                    if occupancy & (1 << to) != 0 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        first_rank_attacks
+    };
 }
 
 impl Slider {
     /*
         Calculating
          - file attacks
-         - rank attacks
          - diagonal attacks
          - antidiagonal attacks
         using Hyperbola Quintessence
         https://www.chessprogramming.org/Hyperbola_Quintessence
+
+        Reversing the bits of a bitboard does not reverse the order of the squares
+        within a rank, so rank attacks are looked up in FIRST_RANK_ATTACKS instead
     */
     fn slider_attacks(&self, square: &Square, occupied_squares: &Bitboard) -> Bitboard {
-        let (mut forward, mut reverse) = (0, 0);
+        if *self == Slider::Rank {
+            return rank_attacks(square, occupied_squares);
+        }
 
         let square_mask = &SQUARE_MASKS[square.0 as usize];
+        let line_mask = square_mask.slider_mask_ex.get(self).unwrap();
 
         // (o-r): masking the file & subtracting the sqaure
-        forward = occupied_squares & square_mask.slider_mask_ex.get(self).unwrap();
-        reverse = forward.reverse_bits();
+        let mut forward = (occupied_squares & line_mask).0;
+        let mut reverse = forward.reverse_bits();
         // (o-2r)
-        forward -= square_mask.bit_mask;
-        reverse -= square_mask.bit_mask.reverse_bits();
+        forward = forward.wrapping_sub(square_mask.bit_mask.0);
+        reverse = reverse.wrapping_sub(square_mask.bit_mask.0.reverse_bits());
         // (o-2r)^rev(o'-2r')
         forward ^= reverse.reverse_bits();
-        forward &= square_mask.slider_mask_ex.get(self).unwrap();
 
-        return forward;
+        return Bitboard(forward) & line_mask;
     }
 }
+
+fn rank_attacks(square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+    let (file, rank_shift) = ((square.0 % 8) as usize, (square.0 / 8) * 8);
+    let inner_occupancy = ((occupied_squares.0 >> rank_shift) >> 1) & 0x3f;
+
+    return Bitboard(FIRST_RANK_ATTACKS[file][inner_occupancy as usize] as u64) << rank_shift;
+}
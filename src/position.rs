@@ -1,5 +1,7 @@
-use crate::bitboard::PiecePlacement;
-use crate::moves::Square;
+use crate::bitboard::{Bitboard, PiecePlacement, SQUARES_BETWEEN};
+use crate::moves::{Move, Square, KING_CASTLE, QUEEN_CASTLE};
+use crate::piece::PieceType;
+use crate::zobrist::{black_to_move_key, castling_rights_key, en_passant_key, piece_key};
 use core::fmt;
 use std::collections::HashMap;
 use std::fmt::write;
@@ -7,7 +9,7 @@ use std::slice::Iter;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Position {
     pub piece_placement: PiecePlacement,
     pub active_color: Color,
@@ -15,6 +17,406 @@ pub struct Position {
     pub en_passant_target: Option<Square>,
     pub half_move_clock: u16,
     pub full_move_number: u16,
+    // Zobrist key of the position, see zobrist.rs
+    pub hash: u64,
+}
+
+// The parts of a position a move cannot be reversed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Undo {
+    pub captured: Option<PieceType>,
+    pub castling_rights: Option<CastlingRights>,
+    pub en_passant_target: Option<Square>,
+    pub half_move_clock: u16,
+    pub hash: u64,
+}
+
+impl Position {
+    pub fn king_square(&self, color: &Color) -> Square {
+        return self.piece_placement.pieces(color, &PieceType::King).lsb();
+    }
+
+    // pieces of both colors attacking the square, only pieces on the occupied squares are
+    // considered, which lets callers remove captured or moved pieces from the board
+    pub fn attackers_to(&self, square: &Square, occupied_squares: &Bitboard) -> Bitboard {
+        let piece_placement = &self.piece_placement;
+        let pieces = |piece: &PieceType| {
+            piece_placement.pieces(&Color::White, piece)
+                | piece_placement.pieces(&Color::Black, piece)
+        };
+        let queens = pieces(&PieceType::Queen);
+
+        let attackers = (PieceType::Pawn.attact_bitboard(
+            &Color::Black,
+            square,
+            occupied_squares,
+            &Bitboard::EMPTY,
+        ) & piece_placement.pieces(&Color::White, &PieceType::Pawn))
+            | (PieceType::Pawn.attact_bitboard(
+                &Color::White,
+                square,
+                occupied_squares,
+                &Bitboard::EMPTY,
+            ) & piece_placement.pieces(&Color::Black, &PieceType::Pawn))
+            | (PieceType::Knight.attact_bitboard(
+                &Color::White,
+                square,
+                occupied_squares,
+                &Bitboard::EMPTY,
+            ) & pieces(&PieceType::Knight))
+            | (PieceType::Bishop.attact_bitboard(
+                &Color::White,
+                square,
+                occupied_squares,
+                &Bitboard::EMPTY,
+            ) & (pieces(&PieceType::Bishop) | queens))
+            | (PieceType::Rook.attact_bitboard(
+                &Color::White,
+                square,
+                occupied_squares,
+                &Bitboard::EMPTY,
+            ) & (pieces(&PieceType::Rook) | queens))
+            | (PieceType::King.attact_bitboard(
+                &Color::White,
+                square,
+                occupied_squares,
+                &Bitboard::EMPTY,
+            ) & pieces(&PieceType::King));
+
+        return attackers & occupied_squares;
+    }
+
+    pub fn is_square_attacked(&self, square: &Square, by: &Color) -> bool {
+        return !(self.attackers_to(square, &self.piece_placement.all_occupancy())
+            & self.piece_placement.occupancy(by))
+        .is_empty();
+    }
+
+    // opponent pieces giving check to the king of the side to move
+    pub fn checkers(&self) -> Bitboard {
+        return self.attackers_to(
+            &self.king_square(&self.active_color),
+            &self.piece_placement.all_occupancy(),
+        ) & self
+            .piece_placement
+            .occupancy(&self.active_color.opponent());
+    }
+
+    // pieces of either color that are the only piece between the king of the given color
+    // and an opponent slider, moving them away would expose the king
+    pub fn blockers_for_king(&self, color: &Color) -> Bitboard {
+        let piece_placement = &self.piece_placement;
+        let opponent = color.opponent();
+        let king_square = self.king_square(color);
+        let occupied_squares = piece_placement.all_occupancy();
+        let queens = piece_placement.pieces(&opponent, &PieceType::Queen);
+
+        // opponent sliders that would attack the king on an empty board
+        let snipers = (PieceType::Rook.attact_bitboard(
+            color,
+            &king_square,
+            &Bitboard::EMPTY,
+            &Bitboard::EMPTY,
+        ) & (piece_placement.pieces(&opponent, &PieceType::Rook) | queens))
+            | (PieceType::Bishop.attact_bitboard(
+                color,
+                &king_square,
+                &Bitboard::EMPTY,
+                &Bitboard::EMPTY,
+            ) & (piece_placement.pieces(&opponent, &PieceType::Bishop) | queens));
+
+        let mut blockers = Bitboard::EMPTY;
+        for sniper_square in snipers {
+            let between = SQUARES_BETWEEN[king_square.0 as usize][sniper_square.0 as usize]
+                & occupied_squares;
+            if between.count() == 1 {
+                blockers |= between;
+            }
+        }
+        return blockers;
+    }
+
+    // pieces of the given color pinned to their own king
+    pub fn pinned(&self, color: &Color) -> Bitboard {
+        return self.blockers_for_king(color) & self.piece_placement.occupancy(color);
+    }
+
+    // whether the legal move puts the opponent king in check, directly or by discovery
+    pub fn gives_check(&self, mv: Move) -> bool {
+        let piece_placement = &self.piece_placement;
+        let us = self.active_color;
+        let (from, to) = (mv.from(), mv.to());
+        let opponent_king = self.king_square(&us.opponent());
+        let (_, piece) = piece_placement
+            .piece_on(&from)
+            .expect("no piece on the from square of the move");
+
+        let (from_bb, to_bb) = (Bitboard::from_square(&from), Bitboard::from_square(&to));
+        let mut occupied_squares = (piece_placement.all_occupancy() & !from_bb) | to_bb;
+        // our pieces that stay where they are
+        let mut standing_pieces = piece_placement.occupancy(&us) & !from_bb;
+        if mv.is_en_passant() {
+            occupied_squares &= !Bitboard::from_square(&en_passant_victim(&to, &us));
+        }
+
+        let mut moved_pieces = vec![(mv.promotion_piece().unwrap_or(piece), to)];
+        if mv.is_castle() {
+            let (rook_from, rook_to) = castling_rook_squares(&self.castling_rights, &us, &mv);
+            // the king may land on the square the rook leaves
+            let (rook_from_bb, rook_to_bb) = (
+                Bitboard::from_square(&rook_from),
+                Bitboard::from_square(&rook_to),
+            );
+            occupied_squares =
+                (piece_placement.all_occupancy() & !from_bb & !rook_from_bb) | to_bb | rook_to_bb;
+            standing_pieces &= !rook_from_bb;
+            moved_pieces.push((PieceType::Rook, rook_to));
+        }
+
+        let direct_check = moved_pieces.iter().any(|(piece, square)| {
+            piece
+                .attact_bitboard(&us, square, &occupied_squares, &Bitboard::EMPTY)
+                .contains(&opponent_king)
+        });
+        let discovered_check =
+            !(self.attackers_to(&opponent_king, &occupied_squares) & standing_pieces).is_empty();
+
+        return direct_check || discovered_check;
+    }
+
+    // plays a legal move of the side to move, the returned record undoes it
+    pub fn make_move(&mut self, mv: Move) -> Undo {
+        let (us, them) = (self.active_color, self.active_color.opponent());
+        let (from, to) = (mv.from(), mv.to());
+        let (_, piece) = self
+            .piece_placement
+            .piece_on(&from)
+            .expect("no piece on the from square of the move");
+
+        let undo = Undo {
+            captured: None,
+            castling_rights: self.castling_rights.clone(),
+            en_passant_target: self.en_passant_target,
+            half_move_clock: self.half_move_clock,
+            hash: self.hash,
+        };
+        self.hash ^= castling_rights_key(&self.castling_rights) ^ en_passant_key(self);
+
+        let captured = if mv.is_en_passant() {
+            self.remove_piece(&them, &PieceType::Pawn, &en_passant_victim(&to, &us));
+            Some(PieceType::Pawn)
+        } else if mv.is_capture() {
+            let (_, captured) = self
+                .piece_placement
+                .piece_on(&to)
+                .expect("no piece on the to square of the capture");
+            self.remove_piece(&them, &captured, &to);
+            Some(captured)
+        } else {
+            None
+        };
+
+        // in Chess960 the king can land where its castling rook stood, so the rook leaves first
+        let castling_rook = if mv.is_castle() {
+            Some(castling_rook_squares(&undo.castling_rights, &us, &mv))
+        } else {
+            None
+        };
+        if let Some((rook_from, _)) = castling_rook {
+            self.remove_piece(&us, &PieceType::Rook, &rook_from);
+        }
+        self.remove_piece(&us, &piece, &from);
+        self.add_piece(&us, &mv.promotion_piece().unwrap_or(piece), &to);
+        if let Some((_, rook_to)) = castling_rook {
+            self.add_piece(&us, &PieceType::Rook, &rook_to);
+        }
+
+        self.revoke_castling_rights(&piece, &from, &to);
+
+        self.en_passant_target = if mv.is_double_pawn_push() {
+            Some(Square((from.0 + to.0) / 2))
+        } else {
+            None
+        };
+
+        if piece == PieceType::Pawn || captured.is_some() {
+            self.half_move_clock = 0;
+        } else {
+            self.half_move_clock += 1;
+        }
+        if us == Color::Black {
+            self.full_move_number += 1;
+        }
+        self.active_color = them;
+
+        self.hash ^=
+            castling_rights_key(&self.castling_rights) ^ en_passant_key(self) ^ black_to_move_key();
+        debug_assert_eq!(self.hash, self.compute_hash(), "hash diverged after {}", mv);
+
+        return Undo { captured, ..undo };
+    }
+
+    // takes back the last move made, with the record make_move returned for it
+    pub fn unmake_move(&mut self, mv: Move, undo: Undo) {
+        let (us, them) = (self.active_color.opponent(), self.active_color);
+        let (from, to) = (mv.from(), mv.to());
+        let (_, piece) = self
+            .piece_placement
+            .piece_on(&to)
+            .expect("no piece on the to square of the move");
+
+        // both castling pieces leave before either goes back, their squares can overlap
+        let castling_rook = if mv.is_castle() {
+            Some(castling_rook_squares(&undo.castling_rights, &us, &mv))
+        } else {
+            None
+        };
+        self.piece_placement.remove_piece(&us, &piece, &to);
+        if let Some((_, rook_to)) = castling_rook {
+            self.piece_placement
+                .remove_piece(&us, &PieceType::Rook, &rook_to);
+        }
+        if mv.is_promotion() {
+            self.piece_placement.add_piece(&us, &PieceType::Pawn, &from);
+        } else {
+            self.piece_placement.add_piece(&us, &piece, &from);
+        }
+        if let Some((rook_from, _)) = castling_rook {
+            self.piece_placement
+                .add_piece(&us, &PieceType::Rook, &rook_from);
+        }
+
+        if let Some(captured) = undo.captured {
+            let captured_square = if mv.is_en_passant() {
+                en_passant_victim(&to, &us)
+            } else {
+                to
+            };
+            self.piece_placement
+                .add_piece(&them, &captured, &captured_square);
+        }
+
+        self.castling_rights = undo.castling_rights;
+        self.en_passant_target = undo.en_passant_target;
+        self.half_move_clock = undo.half_move_clock;
+        self.hash = undo.hash;
+        if us == Color::Black {
+            self.full_move_number -= 1;
+        }
+        self.active_color = us;
+    }
+
+    /*
+        Passes the turn to the opponent, for null move pruning and threat detection. Refused
+        with None when the side to move is in check, as the opponent could then take the king
+    */
+    pub fn make_null_move(&mut self) -> Option<Undo> {
+        if !self.checkers().is_empty() {
+            return None;
+        }
+
+        let undo = Undo {
+            captured: None,
+            castling_rights: self.castling_rights.clone(),
+            en_passant_target: self.en_passant_target,
+            half_move_clock: self.half_move_clock,
+            hash: self.hash,
+        };
+
+        self.hash ^= en_passant_key(self) ^ black_to_move_key();
+        self.en_passant_target = None;
+        self.half_move_clock += 1;
+        if self.active_color == Color::Black {
+            self.full_move_number += 1;
+        }
+        self.active_color = self.active_color.opponent();
+        debug_assert_eq!(
+            self.hash,
+            self.compute_hash(),
+            "hash diverged after a null move"
+        );
+
+        return Some(undo);
+    }
+
+    // takes back the null move make_null_move returned the record for
+    pub fn unmake_null_move(&mut self, undo: Undo) {
+        self.active_color = self.active_color.opponent();
+        if self.active_color == Color::Black {
+            self.full_move_number -= 1;
+        }
+        self.en_passant_target = undo.en_passant_target;
+        self.half_move_clock = undo.half_move_clock;
+        self.hash = undo.hash;
+    }
+
+    // placement changes of make_move, keeping the hash up to date
+    fn add_piece(&mut self, color: &Color, piece: &PieceType, square: &Square) {
+        self.piece_placement.add_piece(color, piece, square);
+        self.hash ^= piece_key(color, piece, square);
+    }
+
+    fn remove_piece(&mut self, color: &Color, piece: &PieceType, square: &Square) {
+        self.piece_placement.remove_piece(color, piece, square);
+        self.hash ^= piece_key(color, piece, square);
+    }
+
+    // a king move loses both castling rights of its color, a castling rook leaving its home
+    // square or being captured on it loses its own
+    fn revoke_castling_rights(&mut self, piece: &PieceType, from: &Square, to: &Square) {
+        let us = self.active_color;
+        let castling_rights = match &mut self.castling_rights {
+            Some(castling_rights) => castling_rights,
+            None => return,
+        };
+
+        for color in Color::iterator() {
+            let king_moved = *color == us && *piece == PieceType::King;
+            let rook_left = |rook: Option<Square>| rook == Some(*from) || rook == Some(*to);
+            castling_rights.revoke(
+                color,
+                king_moved || rook_left(castling_rights.king_side_rook(color)),
+                king_moved || rook_left(castling_rights.queen_side_rook(color)),
+            );
+        }
+
+        if castling_rights.0.is_empty() {
+            self.castling_rights = None;
+        }
+    }
+}
+
+// square of the pawn captured by an en passant capture landing on the given square
+pub fn en_passant_victim(to: &Square, us: &Color) -> Square {
+    match us {
+        Color::White => Square(to.0 - 8),
+        Color::Black => Square(to.0 + 8),
+    }
+}
+
+/*
+    (from, to) squares of the rook for a castling move, with the castling rights from before
+    the move. Whatever the files the king and rook start on, the king lands on the g or c file
+    and the rook next to it on the f or d file
+*/
+fn castling_rook_squares(
+    castling_rights: &Option<CastlingRights>,
+    color: &Color,
+    mv: &Move,
+) -> (Square, Square) {
+    let castling_rights = castling_rights
+        .as_ref()
+        .expect("castling without castling rights");
+    let home_rank_offset = color.home_rank_offset();
+    let (rook_from, rook_to_file) = match mv.flags() {
+        KING_CASTLE => (castling_rights.king_side_rook(color), 5),
+        QUEEN_CASTLE => (castling_rights.queen_side_rook(color), 3),
+        _ => unreachable!("not a castling move"),
+    };
+    return (
+        rook_from.expect("castling without the castling right"),
+        Square(home_rank_offset + rook_to_file),
+    );
 }
 
 impl fmt::Display for Position {
@@ -25,23 +427,17 @@ impl fmt::Display for Position {
             "{}Active color: {}\n",
             position_representation, self.active_color
         );
-        match &self.castling_rights {
-            Some(castling_rights) => {
-                position_representation = format!(
-                    "{}Castling rights:\n{}\n",
-                    position_representation, castling_rights
-                )
-            }
-            None => {}
-        }
-        match &self.en_passant_target {
-            Some(en_passant_target) => {
-                position_representation = format!(
-                    "{}En passant target: {}\n",
-                    position_representation, en_passant_target
-                )
-            }
-            None => {}
+        if let Some(castling_rights) = &self.castling_rights {
+            position_representation = format!(
+                "{}Castling rights:\n{}\n",
+                position_representation, castling_rights
+            )
+        }
+        if let Some(en_passant_target) = &self.en_passant_target {
+            position_representation = format!(
+                "{}En passant target: {}\n",
+                position_representation, en_passant_target
+            )
         }
         position_representation = format!(
             "{}Half move clock: {}\n",
@@ -55,7 +451,7 @@ impl fmt::Display for Position {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter)]
 pub enum Color {
     White,
     Black,
@@ -69,6 +465,21 @@ impl Color {
         }
     }
 
+    pub fn opponent(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+
+    // square index of the first square of the rank the pieces of the color start on
+    pub fn home_rank_offset(&self) -> u8 {
+        match self {
+            Color::White => 0,
+            Color::Black => 56,
+        }
+    }
+
     pub fn iterator() -> Iter<'static, Color> {
         static COLORS: [Color; 2] = [Color::White, Color::Black];
         COLORS.iter()
@@ -81,26 +492,88 @@ impl fmt::Display for Color {
     }
 }
 
-#[derive(Debug)]
-// king side and queen side castling
-pub struct CastlingTypes(pub bool, pub bool);
+#[derive(Debug, Clone, PartialEq, Eq)]
+/*
+    Files of the king side and queen side castling rooks, None without the right. Storing the
+    files instead of flags lets the rooks start anywhere, as in Chess960
+*/
+pub struct CastlingTypes(pub Option<u8>, pub Option<u8>);
+
+impl CastlingTypes {
+    // the rights of the standard starting position, rooks on the h and a files
+    pub fn standard() -> CastlingTypes {
+        return CastlingTypes(Some(7), Some(0));
+    }
+}
 
 impl fmt::Display for CastlingTypes {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\tKing side: {}\n\tQueen side: {}", self.0, self.1)
+        let file = |rook_file: &Option<u8>| match rook_file {
+            Some(file) => ((b'a' + file) as char).to_string(),
+            None => String::from("-"),
+        };
+        write!(
+            f,
+            "\tKing side: {}\n\tQueen side: {}",
+            file(&self.0),
+            file(&self.1)
+        )
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CastlingRights(pub HashMap<Color, CastlingTypes>);
 
+impl CastlingRights {
+    pub fn king_side(&self, color: &Color) -> bool {
+        return self.king_side_rook(color).is_some();
+    }
+
+    pub fn queen_side(&self, color: &Color) -> bool {
+        return self.queen_side_rook(color).is_some();
+    }
+
+    // home square of the rook the king may castle with
+    pub fn king_side_rook(&self, color: &Color) -> Option<Square> {
+        let rook_file = self
+            .0
+            .get(color)
+            .and_then(|castling_types| castling_types.0)?;
+        return Some(Square(color.home_rank_offset() + rook_file));
+    }
+
+    pub fn queen_side_rook(&self, color: &Color) -> Option<Square> {
+        let rook_file = self
+            .0
+            .get(color)
+            .and_then(|castling_types| castling_types.1)?;
+        return Some(Square(color.home_rank_offset() + rook_file));
+    }
+
+    // a color left without any castling right is removed from the map
+    pub fn revoke(&mut self, color: &Color, king_side: bool, queen_side: bool) {
+        if let Some(castling_types) = self.0.get_mut(color) {
+            if king_side {
+                castling_types.0 = None;
+            }
+            if queen_side {
+                castling_types.1 = None;
+            }
+            if castling_types.0.is_none() && castling_types.1.is_none() {
+                self.0.remove(color);
+            }
+        }
+    }
+}
+
 impl fmt::Display for CastlingRights {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let no_castling = CastlingTypes(None, None);
         write!(
             f,
             "White: \n{}\nBlack: \n{}",
-            self.0.get(&Color::White).unwrap(),
-            self.0.get(&Color::Black).unwrap(),
+            self.0.get(&Color::White).unwrap_or(&no_castling),
+            self.0.get(&Color::Black).unwrap_or(&no_castling),
         )
     }
 }
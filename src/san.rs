@@ -0,0 +1,224 @@
+use core::fmt;
+
+use crate::{
+    move_generator::generate_all_moves,
+    moves::{Move, Square, KING_CASTLE, QUEEN_CASTLE},
+    piece::PieceType,
+    position::{Color, Position},
+};
+
+/*
+    Standard Algebraic Notation
+    https://www.chessprogramming.org/Algebraic_Chess_Notation#Standard_Algebraic_Notation_.28SAN.29
+*/
+
+// why a SAN move could not be turned into a legal move, every variant holds the SAN text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanError {
+    InvalidSyntax(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSyntax(san) => write!(f, "invalid SAN move {:?}", san),
+            Self::IllegalMove(san) => write!(f, "no legal move matches {}", san),
+            Self::AmbiguousMove(san) => write!(f, "more than one legal move matches {}", san),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+// the parts of a SAN move that are not castling
+struct SanMove {
+    piece: PieceType,
+    from_file: Option<u8>,
+    from_rank: Option<u8>,
+    to: Square,
+    promotion: Option<PieceType>,
+}
+
+impl Position {
+    /*
+        The legal move the SAN text stands for. Check and mate markers and annotations such
+        as "!?" after the move are ignored, as is a missing or superfluous capture marker.
+        Castling is accepted as O-O and O-O-O or with zeros
+    */
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let invalid_syntax = || SanError::InvalidSyntax(san.to_string());
+        let move_text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let legal_moves = generate_all_moves(self);
+
+        let castle_flag = match move_text {
+            "O-O" | "0-0" => Some(KING_CASTLE),
+            "O-O-O" | "0-0-0" => Some(QUEEN_CASTLE),
+            _ => None,
+        };
+        let candidates: Vec<Move> = match castle_flag {
+            Some(flag) => legal_moves
+                .into_iter()
+                .filter(|mv| mv.flags() == flag)
+                .collect(),
+            None => {
+                let san_move = parse_san_move(move_text).ok_or_else(invalid_syntax)?;
+                legal_moves
+                    .into_iter()
+                    .filter(|mv| self.matches_san_move(mv, &san_move))
+                    .collect()
+            }
+        };
+
+        match candidates[..] {
+            [mv] => return Ok(mv),
+            [] => return Err(SanError::IllegalMove(san.to_string())),
+            _ => return Err(SanError::AmbiguousMove(san.to_string())),
+        }
+    }
+
+    // the SAN of a legal move, with the file, rank or square of the moving piece where
+    // another piece of the same type could reach the target square too
+    pub fn to_san(&self, mv: Move) -> String {
+        let mut san = match mv.flags() {
+            KING_CASTLE => String::from("O-O"),
+            QUEEN_CASTLE => String::from("O-O-O"),
+            _ => self.san_without_suffix(mv),
+        };
+
+        let mut position = self.clone();
+        position.make_move(mv);
+        if !position.checkers().is_empty() {
+            san.push(match generate_all_moves(&position).is_empty() {
+                true => '#',
+                false => '+',
+            });
+        }
+        return san;
+    }
+
+    fn san_without_suffix(&self, mv: Move) -> String {
+        let (from, to) = (mv.from(), mv.to());
+        let (_, piece) = self
+            .piece_placement
+            .piece_on(&from)
+            .expect("no piece on the from square of the move");
+        let mut san = String::new();
+
+        if piece == PieceType::Pawn {
+            if mv.is_capture() {
+                san.push((b'a' + from.0 % 8) as char);
+            }
+        } else {
+            san.push(piece.piece_char(&Color::White));
+
+            let others: Vec<Square> = generate_all_moves(self)
+                .into_iter()
+                .filter(|other| {
+                    other.to() == to
+                        && other.from() != from
+                        && !other.is_castle()
+                        && self
+                            .piece_placement
+                            .piece_on(&other.from())
+                            .map(|(_, other_piece)| other_piece)
+                            == Some(piece)
+                })
+                .map(|other| other.from())
+                .collect();
+            if !others.is_empty() {
+                let file = (b'a' + from.0 % 8) as char;
+                let rank = (b'1' + from.0 / 8) as char;
+                if others.iter().all(|other| other.0 % 8 != from.0 % 8) {
+                    san.push(file);
+                } else if others.iter().all(|other| other.0 / 8 != from.0 / 8) {
+                    san.push(rank);
+                } else {
+                    san.push(file);
+                    san.push(rank);
+                }
+            }
+        }
+
+        if mv.is_capture() {
+            san.push('x');
+        }
+        san += &to.to_string();
+        if let Some(promotion_piece) = mv.promotion_piece() {
+            san.push('=');
+            san.push(promotion_piece.piece_char(&Color::White));
+        }
+        return san;
+    }
+
+    fn matches_san_move(&self, mv: &Move, san_move: &SanMove) -> bool {
+        let from = mv.from();
+        return !mv.is_castle()
+            && mv.to() == san_move.to
+            && mv.promotion_piece() == san_move.promotion
+            && san_move.from_file.is_none_or(|file| from.0 % 8 == file)
+            && san_move.from_rank.is_none_or(|rank| from.0 / 8 == rank)
+            && self.piece_placement.piece_on(&from).map(|(_, piece)| piece)
+                == Some(san_move.piece);
+    }
+}
+
+// [piece][from file][from rank][x]<to square>[[=]promotion piece], None if malformed
+fn parse_san_move(move_text: &str) -> Option<SanMove> {
+    let mut chars: Vec<char> = move_text.chars().collect();
+
+    // piece letters are upper case, a lower case b is the b file
+    let piece = match chars.first() {
+        Some(piece_char) if piece_char.is_ascii_uppercase() => {
+            let piece = PieceType::from_char(*piece_char)?;
+            chars.remove(0);
+            piece
+        }
+        _ => PieceType::Pawn,
+    };
+
+    let mut promotion = None;
+    if piece == PieceType::Pawn {
+        if let Some(last) = chars.last() {
+            if last.is_ascii_uppercase() {
+                promotion = match PieceType::from_char(*last)? {
+                    PieceType::Pawn | PieceType::King => return None,
+                    promotion_piece => Some(promotion_piece),
+                };
+                chars.pop();
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+            }
+        }
+    }
+
+    if chars.len() < 2 {
+        return None;
+    }
+    let to = chars.split_off(chars.len() - 2).iter().collect::<String>();
+    let to = to.parse::<Square>().ok()?;
+    if chars.last() == Some(&'x') {
+        chars.pop();
+    }
+
+    let (mut from_file, mut from_rank) = (None, None);
+    for disambiguation in chars {
+        match disambiguation {
+            'a'..='h' if from_file.is_none() && from_rank.is_none() => {
+                from_file = Some(disambiguation as u8 - b'a')
+            }
+            '1'..='8' if from_rank.is_none() => from_rank = Some(disambiguation as u8 - b'1'),
+            _ => return None,
+        }
+    }
+
+    return Some(SanMove {
+        piece,
+        from_file,
+        from_rank,
+        to,
+        promotion,
+    });
+}
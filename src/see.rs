@@ -0,0 +1,143 @@
+use crate::{
+    bitboard::Bitboard,
+    moves::{Move, Square},
+    piece::PieceType,
+    position::{Color, Position},
+};
+
+// material values used to resolve exchanges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PieceValues {
+    pub pawn: i32,
+    pub knight: i32,
+    pub bishop: i32,
+    pub rook: i32,
+    pub queen: i32,
+    pub king: i32,
+}
+
+impl PieceValues {
+    pub fn value(&self, piece: &PieceType) -> i32 {
+        match piece {
+            PieceType::Pawn => self.pawn,
+            PieceType::Knight => self.knight,
+            PieceType::Bishop => self.bishop,
+            PieceType::Rook => self.rook,
+            PieceType::Queen => self.queen,
+            PieceType::King => self.king,
+        }
+    }
+}
+
+impl Default for PieceValues {
+    fn default() -> Self {
+        return PieceValues {
+            pawn: 100,
+            knight: 320,
+            bishop: 330,
+            rook: 500,
+            queen: 900,
+            king: 20000,
+        };
+    }
+}
+
+impl Position {
+    // static exchange evaluation of the move with the default piece values
+    pub fn see(&self, mv: Move) -> i32 {
+        return self.see_with_values(mv, &PieceValues::default());
+    }
+
+    pub fn see_ge(&self, mv: Move, threshold: i32) -> bool {
+        return self.see(mv) >= threshold;
+    }
+
+    pub fn see_ge_with_values(&self, mv: Move, threshold: i32, piece_values: &PieceValues) -> bool {
+        return self.see_with_values(mv, piece_values) >= threshold;
+    }
+
+    /*
+        Material balance for the side to move after both sides keep recapturing on the
+        target square with their least valuable attacker, each side free to stop when
+        going on would lose material. Sliders behind a capturing piece join the exchange
+        once it has left its square. Pins are not taken into account
+        https://www.chessprogramming.org/SEE_-_The_Swap_Algorithm
+    */
+    pub fn see_with_values(&self, mv: Move, piece_values: &PieceValues) -> i32 {
+        if mv.is_castle() || mv.is_null() {
+            return 0;
+        }
+
+        let piece_placement = &self.piece_placement;
+        let (from, to) = (mv.from(), mv.to());
+        let (_, moving_piece) = piece_placement
+            .piece_on(&from)
+            .expect("no piece on the from square of the move");
+
+        let mut occupied_squares = piece_placement.all_occupancy() & !Bitboard::from_square(&from);
+        let mut gains: Vec<i32> = Vec::with_capacity(32);
+        gains.push(if mv.is_en_passant() {
+            let victim = match self.active_color {
+                Color::White => to.0 - 8,
+                Color::Black => to.0 + 8,
+            };
+            occupied_squares &= !Bitboard(1 << victim);
+            piece_values.pawn
+        } else {
+            match piece_placement.piece_on(&to) {
+                Some((_, captured)) => piece_values.value(&captured),
+                None => 0,
+            }
+        });
+
+        // value of the piece standing on the target square, the next one to be captured
+        let mut attacker_value = piece_values.value(&moving_piece);
+        if let Some(promotion_piece) = mv.promotion_piece() {
+            gains[0] += piece_values.value(&promotion_piece) - piece_values.pawn;
+            attacker_value = piece_values.value(&promotion_piece);
+        }
+
+        let mut side = self.active_color.opponent();
+        loop {
+            let attackers = self.attackers_to(&to, &occupied_squares);
+            let side_attackers = attackers & piece_placement.occupancy(&side);
+            let (piece, square) = match self.least_valuable_attacker(&side, &side_attackers) {
+                Some(least_valuable_attacker) => least_valuable_attacker,
+                None => break,
+            };
+            // the king can't capture a defended piece
+            if piece == PieceType::King
+                && !(attackers & piece_placement.occupancy(&side.opponent())).is_empty()
+            {
+                break;
+            }
+
+            gains.push(attacker_value - gains[gains.len() - 1]);
+            attacker_value = piece_values.value(&piece);
+            occupied_squares &= !Bitboard::from_square(&square);
+            side = side.opponent();
+        }
+
+        // every side picks the better of capturing and standing pat, from the last capture back
+        while gains.len() > 1 {
+            let last = gains.pop().unwrap();
+            let previous = gains.len() - 1;
+            gains[previous] = -(-gains[previous]).max(last);
+        }
+        return gains[0];
+    }
+
+    fn least_valuable_attacker(
+        &self,
+        color: &Color,
+        attackers: &Bitboard,
+    ) -> Option<(PieceType, Square)> {
+        for piece in PieceType::iterator() {
+            let piece_attackers = attackers & self.piece_placement.pieces(color, piece);
+            if !piece_attackers.is_empty() {
+                return Some((*piece, piece_attackers.lsb()));
+            }
+        }
+        return None;
+    }
+}
@@ -0,0 +1,79 @@
+use core::fmt;
+
+use crate::{
+    move_generator::generate_all_moves,
+    moves::{Move, Square, KING_CASTLE, QUEEN_CASTLE},
+    position::Position,
+};
+
+/*
+    Moves in the long algebraic notation of the UCI protocol: from and to square followed by
+    the promotion piece, e2e4, e7e8q. Castling is the king move to its target square, e1g1,
+    or in Chess960 mode the king capturing its own rook, e1h1
+    https://www.chessprogramming.org/UCI
+*/
+
+// why a UCI move could not be turned into a legal move, every variant holds the UCI text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UciMoveError {
+    InvalidSyntax(String),
+    IllegalMove(String),
+}
+
+impl fmt::Display for UciMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSyntax(uci) => write!(f, "invalid UCI move {:?}", uci),
+            Self::IllegalMove(uci) => write!(f, "{} is not a legal move", uci),
+        }
+    }
+}
+
+impl std::error::Error for UciMoveError {}
+
+impl Position {
+    /*
+        The legal move, with its flags, the UCI text stands for. Both castling forms are
+        accepted. When a Chess960 king can also step to its castling target square without
+        castling, the target square form is read as that plain king move
+    */
+    pub fn parse_uci_move(&self, uci: &str) -> Result<Move, UciMoveError> {
+        let parsed = uci
+            .parse::<Move>()
+            .map_err(|_| UciMoveError::InvalidSyntax(uci.to_string()))?;
+        let (from, to) = (parsed.from(), parsed.to());
+
+        let mut candidates: Vec<Move> = generate_all_moves(self)
+            .into_iter()
+            .filter(|mv| mv.from() == from && mv.promotion_piece() == parsed.promotion_piece())
+            .filter(|mv| mv.to() == to || self.castling_rook(mv) == Some(to))
+            .collect();
+        // the king capturing its own rook is never a plain move
+        if candidates.len() > 1 {
+            candidates.retain(|mv| mv.is_castle() != (mv.to() == to));
+        }
+
+        match candidates.first() {
+            Some(mv) => return Ok(*mv),
+            None => return Err(UciMoveError::IllegalMove(uci.to_string())),
+        }
+    }
+
+    // the UCI text of a legal move, castling as king takes rook in Chess960 mode
+    pub fn to_uci(&self, mv: Move, chess960: bool) -> String {
+        match self.castling_rook(&mv) {
+            Some(rook_square) if chess960 => return format!("{}{}", mv.from(), rook_square),
+            _ => return mv.to_string(),
+        }
+    }
+
+    // the square of the rook a castling move castles with
+    fn castling_rook(&self, mv: &Move) -> Option<Square> {
+        let castling_rights = self.castling_rights.as_ref()?;
+        match mv.flags() {
+            KING_CASTLE => return castling_rights.king_side_rook(&self.active_color),
+            QUEEN_CASTLE => return castling_rights.queen_side_rook(&self.active_color),
+            _ => return None,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use core::fmt;
+
+use crate::{
+    bitboard::{RANK_1, RANK_8},
+    moves::Square,
+    piece::PieceType,
+    position::{en_passant_victim, Color, Position},
+};
+
+// the rule of a legal position that validate found broken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionError {
+    MissingKing(Color),
+    TooManyKings(Color),
+    PawnOnBackRank(Square),
+    OpponentInCheck,
+    // color and whether the right is king side, the king or the rook left its home square
+    InvalidCastlingRight(Color, bool),
+    InvalidEnPassantTarget(Square),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingKing(color) => write!(f, "{} has no king", color),
+            Self::TooManyKings(color) => write!(f, "{} has more than one king", color),
+            Self::PawnOnBackRank(square) => write!(f, "pawn on the back rank square {}", square),
+            Self::OpponentInCheck => write!(f, "the side not to move is in check"),
+            Self::InvalidCastlingRight(color, king_side) => write!(
+                f,
+                "{} {} side castling right without the king and rook on their home squares",
+                color,
+                if *king_side { "king" } else { "queen" }
+            ),
+            Self::InvalidEnPassantTarget(square) => write!(
+                f,
+                "en passant target {} is not behind a pawn that just moved two squares",
+                square
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+impl Position {
+    /*
+        Checks the rules a position reachable in a game has to follow, which parse_fen alone
+        doesn't: one king per side, no pawns on the back ranks, the side not to move not in
+        check, castling rights backed by the king and rook on their home rank and an en
+        passant target on the third or sixth rank behind the pawn that just moved
+    */
+    pub fn validate(&self) -> Result<(), PositionError> {
+        let piece_placement = &self.piece_placement;
+
+        for color in Color::iterator() {
+            match piece_placement.pieces(color, &PieceType::King).count() {
+                0 => return Err(PositionError::MissingKing(*color)),
+                1 => {}
+                _ => return Err(PositionError::TooManyKings(*color)),
+            }
+        }
+
+        let pawns = piece_placement.pieces(&Color::White, &PieceType::Pawn)
+            | piece_placement.pieces(&Color::Black, &PieceType::Pawn);
+        let back_rank_pawns = pawns & (RANK_1 | RANK_8);
+        if !back_rank_pawns.is_empty() {
+            return Err(PositionError::PawnOnBackRank(back_rank_pawns.lsb()));
+        }
+
+        let (us, them) = (self.active_color, self.active_color.opponent());
+        if self.is_square_attacked(&self.king_square(&them), &us) {
+            return Err(PositionError::OpponentInCheck);
+        }
+
+        if let Some(castling_rights) = &self.castling_rights {
+            for color in Color::iterator() {
+                let king = self.king_square(color);
+                let on_home_rank = king.0 / 8 == color.home_rank_offset() / 8;
+                // the rook has to stand on its square, on the side of the king it castles to
+                let rook_in_place = |rook: &Square, king_side: bool| {
+                    piece_placement.piece_on(rook) == Some((*color, PieceType::Rook))
+                        && (rook.0 > king.0) == king_side
+                };
+
+                if let Some(rook) = castling_rights.king_side_rook(color) {
+                    if !on_home_rank || !rook_in_place(&rook, true) {
+                        return Err(PositionError::InvalidCastlingRight(*color, true));
+                    }
+                }
+                if let Some(rook) = castling_rights.queen_side_rook(color) {
+                    if !on_home_rank || !rook_in_place(&rook, false) {
+                        return Err(PositionError::InvalidCastlingRight(*color, false));
+                    }
+                }
+            }
+        }
+
+        if let Some(en_passant_target) = &self.en_passant_target {
+            // the rank the pawn of the opponent skipped over
+            let target_rank = match us {
+                Color::White => 5,
+                Color::Black => 2,
+            };
+            if en_passant_target.0 / 8 != target_rank
+                || piece_placement.piece_on(&en_passant_victim(en_passant_target, &us))
+                    != Some((them, PieceType::Pawn))
+            {
+                return Err(PositionError::InvalidEnPassantTarget(*en_passant_target));
+            }
+        }
+
+        return Ok(());
+    }
+}
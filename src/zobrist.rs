@@ -0,0 +1,128 @@
+use lazy_static::lazy_static;
+
+use crate::{
+    bitboard::PAWN_ATTACKS,
+    moves::Square,
+    piece::PieceType,
+    position::{CastlingRights, Color, Position},
+};
+
+/*
+    Zobrist hashing
+    https://www.chessprogramming.org/Zobrist_Hashing
+
+    The key of a position is the XOR of a random number for every piece on its square, one
+    for the side to move being black, one per castling right and one for the file of a
+    capturable en passant target. Making a move only has to XOR in and out the parts that changed
+*/
+struct ZobristKeys {
+    pieces: [[[u64; 64]; 6]; 2],
+    black_to_move: u64,
+    // by color and file of the castling rook
+    castling_rights: [[u64; 8]; 2],
+    en_passant_files: [u64; 8],
+}
+
+// splitmix64 with a fixed seed, so keys are identical across runs and builds
+struct KeyGenerator(u64);
+
+impl KeyGenerator {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut key = self.0;
+        key = (key ^ (key >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return key ^ (key >> 31);
+    }
+}
+
+lazy_static! {
+    static ref ZOBRIST_KEYS: ZobristKeys = {
+        let mut key_generator = KeyGenerator(0x6a09e667f3bcc908);
+
+        let mut pieces = [[[0; 64]; 6]; 2];
+        for color_keys in pieces.iter_mut() {
+            for piece_keys in color_keys.iter_mut() {
+                for key in piece_keys.iter_mut() {
+                    *key = key_generator.next();
+                }
+            }
+        }
+        let black_to_move = key_generator.next();
+        let castling_rights =
+            core::array::from_fn(|_| core::array::from_fn(|_| key_generator.next()));
+        let en_passant_files = core::array::from_fn(|_| key_generator.next());
+
+        ZobristKeys {
+            pieces,
+            black_to_move,
+            castling_rights,
+            en_passant_files,
+        }
+    };
+}
+
+pub fn piece_key(color: &Color, piece: &PieceType, square: &Square) -> u64 {
+    return ZOBRIST_KEYS.pieces[*color as usize][*piece as usize][square.0 as usize];
+}
+
+pub fn black_to_move_key() -> u64 {
+    return ZOBRIST_KEYS.black_to_move;
+}
+
+pub fn castling_rights_key(castling_rights: &Option<CastlingRights>) -> u64 {
+    let castling_rights = match castling_rights {
+        Some(castling_rights) => castling_rights,
+        None => return 0,
+    };
+
+    let mut key = 0;
+    for color in Color::iterator() {
+        for rook in [
+            castling_rights.king_side_rook(color),
+            castling_rights.queen_side_rook(color),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            key ^= ZOBRIST_KEYS.castling_rights[*color as usize][(rook.0 % 8) as usize];
+        }
+    }
+    return key;
+}
+
+// the en passant file only counts when a pawn of the side to move can capture on the target,
+// otherwise the position is the same as the one without a target, e.g. for repetitions
+pub fn en_passant_key(position: &Position) -> u64 {
+    let en_passant_target = match position.en_passant_target {
+        Some(en_passant_target) => en_passant_target,
+        None => return 0,
+    };
+
+    let us = position.active_color;
+    let capturing_pawns = PAWN_ATTACKS.get(&us.opponent()).unwrap()[en_passant_target.0 as usize]
+        & position.piece_placement.pieces(&us, &PieceType::Pawn);
+    if capturing_pawns.is_empty() {
+        return 0;
+    }
+    return ZOBRIST_KEYS.en_passant_files[(en_passant_target.0 % 8) as usize];
+}
+
+impl Position {
+    // the key computed from scratch, make_move keeps self.hash equal to it incrementally
+    pub fn compute_hash(&self) -> u64 {
+        let mut hash = 0;
+        for color in Color::iterator() {
+            for piece in PieceType::iterator() {
+                for square in self.piece_placement.pieces(color, piece) {
+                    hash ^= piece_key(color, piece, &square);
+                }
+            }
+        }
+
+        if self.active_color == Color::Black {
+            hash ^= black_to_move_key();
+        }
+        return hash ^ castling_rights_key(&self.castling_rights) ^ en_passant_key(self);
+    }
+}
@@ -0,0 +1,56 @@
+use jaingo_unchaind_rust::{
+    bitboard::Bitboard, fen_parser::parse_fen, moves::Square, position::Color,
+};
+
+fn squares(squares: &[&str]) -> Bitboard {
+    return squares.iter().fold(Bitboard::EMPTY, |bitboard, square| {
+        bitboard | Bitboard::from_square(&square.parse::<Square>().unwrap())
+    });
+}
+
+#[test]
+fn attackers_to_both_colors() {
+    let position = parse_fen("4k3/8/4p3/8/4B3/2N5/8/4K3 w - - 0 1").unwrap();
+    let occupied_squares = position.piece_placement.all_occupancy();
+
+    assert_eq!(
+        position.attackers_to(&"d5".parse().unwrap(), &occupied_squares),
+        squares(&["e4", "c3", "e6"])
+    );
+    // removing the bishop from the occupancy removes it from the attackers
+    assert_eq!(
+        position.attackers_to(
+            &"d5".parse().unwrap(),
+            &(occupied_squares & !squares(&["e4"]))
+        ),
+        squares(&["c3", "e6"])
+    );
+    assert!(position.is_square_attacked(&"d5".parse().unwrap(), &Color::Black));
+    assert!(!position.is_square_attacked(&"d4".parse().unwrap(), &Color::Black));
+}
+
+#[test]
+fn checkers() {
+    let position = parse_fen("4k3/8/8/8/8/5n2/8/4K2r w - - 0 1").unwrap();
+    assert_eq!(position.checkers(), squares(&["f3", "h1"]));
+
+    let position = parse_fen("4k3/8/8/8/8/8/8/4K2R b - - 0 1").unwrap();
+    assert_eq!(position.checkers(), Bitboard::EMPTY);
+}
+
+#[test]
+fn pinned_and_blockers_for_king() {
+    let position = parse_fen("4k3/4n3/8/8/1b6/8/3P4/4K2R w - - 0 1").unwrap();
+    assert_eq!(position.pinned(&Color::White), squares(&["d2"]));
+    assert_eq!(position.blockers_for_king(&Color::White), squares(&["d2"]));
+    assert_eq!(position.pinned(&Color::Black), Bitboard::EMPTY);
+
+    // a piece of the attacker between the king and the slider is a discovered check blocker
+    let position = parse_fen("4k3/8/8/8/4N3/8/8/K3R3 w - - 0 1").unwrap();
+    assert_eq!(position.blockers_for_king(&Color::Black), squares(&["e4"]));
+    assert_eq!(position.pinned(&Color::Black), Bitboard::EMPTY);
+
+    // two pieces between the king and the slider pin neither
+    let position = parse_fen("4k3/4n3/4p3/8/8/8/8/K3R3 b - - 0 1").unwrap();
+    assert_eq!(position.blockers_for_king(&Color::Black), Bitboard::EMPTY);
+}
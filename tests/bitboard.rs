@@ -0,0 +1,65 @@
+use jaingo_unchaind_rust::{bitboard::Bitboard, moves::Square};
+
+fn square(name: &str) -> Square {
+    return name.parse::<Square>().unwrap();
+}
+
+#[test]
+fn lsb_msb_and_count() {
+    let bitboard = Bitboard::from_square(&square("c2")) | Bitboard::from_square(&square("f7"));
+    assert_eq!(bitboard.count(), 2);
+    assert_eq!(bitboard.lsb(), square("c2"));
+    assert_eq!(bitboard.msb(), square("f7"));
+    assert!(bitboard.contains(&square("f7")));
+    assert!(!bitboard.contains(&square("f6")));
+    assert_eq!(Bitboard::FULL.count(), 64);
+    assert_eq!(Bitboard::FULL.msb(), Square(63));
+}
+
+#[test]
+fn pop_lsb_and_iteration_go_from_a1_to_h8() {
+    let mut bitboard = Bitboard(0x8000_0000_0000_0101);
+    let squares: Vec<Square> = bitboard.into_iter().collect();
+    assert_eq!(squares, vec![Square(0), Square(8), Square(63)]);
+
+    assert_eq!(bitboard.pop_lsb(), Square(0));
+    assert_eq!(bitboard.pop_lsb(), Square(8));
+    assert_eq!(bitboard, Bitboard(0x8000_0000_0000_0000));
+    assert_eq!(Bitboard::EMPTY.squares().next(), None);
+}
+
+#[test]
+fn operators_work_on_the_square_sets() {
+    let (a, b) = (Bitboard(0b1100), Bitboard(0b1010));
+    assert_eq!(a & b, Bitboard(0b1000));
+    assert_eq!(a | b, Bitboard(0b1110));
+    assert_eq!(a ^ b, Bitboard(0b0110));
+    assert_eq!(!Bitboard::EMPTY, Bitboard::FULL);
+
+    let mut c = a;
+    c ^= b;
+    c |= Bitboard(1);
+    c &= !Bitboard(0b10);
+    assert_eq!(c, Bitboard(0b0101));
+}
+
+#[test]
+fn shifts_drop_squares_leaving_the_board() {
+    let corners = Bitboard::from_square(&square("a1")) | Bitboard::from_square(&square("h8"));
+    assert_eq!(corners.north(), Bitboard::from_square(&square("a2")));
+    assert_eq!(corners.south(), Bitboard::from_square(&square("h7")));
+    assert_eq!(corners.east(), Bitboard::from_square(&square("b1")));
+    assert_eq!(corners.west(), Bitboard::from_square(&square("g8")));
+    assert_eq!(corners.north_east(), Bitboard::from_square(&square("b2")));
+    assert_eq!(corners.south_west(), Bitboard::from_square(&square("g7")));
+    assert_eq!(corners.north_west(), Bitboard::EMPTY);
+    assert_eq!(corners.south_east(), Bitboard::EMPTY);
+}
+
+#[test]
+fn debug_draws_the_board_from_the_8th_rank() {
+    let bitboard = Bitboard::from_square(&square("a1")) | Bitboard::from_square(&square("h8"));
+    let expected =
+        String::from(". . . . . . . 1 \n") + &". . . . . . . . \n".repeat(6) + "1 . . . . . . . \n";
+    assert_eq!(format!("{:?}", bitboard), expected);
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+use jaingo_unchaind_rust::{
+    chess960::{back_rank, start_position, start_position_fen},
+    fen_parser::{parse_fen, INITIAL_POSITION},
+    move_generator::generate_all_moves,
+    moves::{Move, KING_CASTLE, QUEEN_CASTLE},
+    perft::{parse_perft_suite, perft},
+    position::Color,
+};
+
+#[test]
+fn start_position_ids() {
+    assert_eq!(start_position_fen(518).unwrap(), INITIAL_POSITION);
+    assert_eq!(
+        start_position_fen(0).unwrap(),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+    );
+    assert_eq!(
+        back_rank(959).unwrap().iter().collect::<String>(),
+        "rkrnnqbb"
+    );
+    assert_eq!(start_position_fen(960), None);
+
+    let mut back_ranks = HashSet::new();
+    for id in 0..960 {
+        let back_rank = back_rank(id).unwrap();
+        let files = |piece: char| -> Vec<usize> {
+            return (0..8).filter(|file| back_rank[*file] == piece).collect();
+        };
+        let (bishops, rooks, king) = (files('b'), files('r'), files('k'));
+        assert_eq!(bishops.len(), 2);
+        assert_ne!(bishops[0] % 2, bishops[1] % 2, "bishops of {}", id);
+        assert!(rooks[0] < king[0] && king[0] < rooks[1], "king of {}", id);
+        assert!(back_ranks.insert(back_rank));
+
+        let position = start_position(id).unwrap();
+        assert_eq!(position.validate(), Ok(()));
+        assert_eq!(parse_fen(&position.to_fen()).unwrap(), position);
+    }
+}
+
+#[test]
+fn shredder_and_x_fen_castling_fields() {
+    let position = parse_fen(INITIAL_POSITION).unwrap();
+    assert_eq!(
+        position.to_shredder_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1"
+    );
+    assert_eq!(
+        parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1").unwrap(),
+        position
+    );
+
+    // X-FEN names a rook by its file when another one stands farther out on the same side
+    let fen = "rr2k3/8/8/8/8/8/8/4K1RR w Gb - 0 1";
+    let position = parse_fen(fen).unwrap();
+    let castling_rights = position.castling_rights.as_ref().unwrap();
+    assert_eq!(
+        castling_rights
+            .king_side_rook(&Color::White)
+            .unwrap()
+            .to_string(),
+        "g1"
+    );
+    assert_eq!(position.to_fen(), fen);
+    assert_eq!(
+        position.to_shredder_fen(),
+        "rr2k3/8/8/8/8/8/8/4K1RR w Gb - 0 1"
+    );
+    assert_eq!(
+        parse_fen("rr2k3/8/8/8/8/8/8/4K1RR w Ka - 0 1")
+            .unwrap()
+            .to_fen(),
+        "rr2k3/8/8/8/8/8/8/4K1RR w Kq - 0 1"
+    );
+}
+
+fn castling_moves(fen: &str) -> Vec<Move> {
+    return generate_all_moves(&parse_fen(fen).unwrap())
+        .into_iter()
+        .filter(|mv| mv.is_castle())
+        .collect();
+}
+
+#[test]
+fn chess960_castling() {
+    // the king lands on the square of the rook
+    let fen = "4k3/8/8/8/8/8/8/5KR1 w G - 0 1";
+    let mut position = parse_fen(fen).unwrap();
+    let castle = castling_moves(fen);
+    assert_eq!(castle.len(), 1);
+    assert_eq!(castle[0].flags(), KING_CASTLE);
+    let original = position.clone();
+    let undo = position.make_move(castle[0]);
+    assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+    position.unmake_move(castle[0], undo);
+    assert_eq!(position, original);
+
+    // queen side castling can move the king towards the rook or away from it
+    let castle = castling_moves("4k3/8/8/8/8/8/8/1R4K1 w B - 0 1");
+    assert_eq!(castle[0].to_string(), "g1c1");
+    // the king already stands on its destination
+    let castle = castling_moves("4k3/8/8/8/8/8/8/R1K5 w A - 0 1");
+    assert_eq!((castle.len(), castle[0].flags()), (1, QUEEN_CASTLE));
+    assert_eq!(castle[0].from(), castle[0].to());
+
+    // the rook shields the king destination from a rook on the home rank
+    assert!(castling_moves("4k3/8/8/8/8/8/8/5KRr w G - 0 1").is_empty());
+    // squares the rook crosses have to be empty, even if the king doesn't cross them
+    assert!(castling_moves("4k3/8/8/8/8/8/8/RN1K4 w A - 0 1").is_empty());
+    assert_eq!(castling_moves("4k3/8/8/8/8/8/8/R2K4 w A - 0 1").len(), 1);
+}
+
+// checks every depth of the suite with at most max_nodes nodes
+fn check_chess960_perft_suite(max_nodes: u64) {
+    let entries = parse_perft_suite(include_str!("data/chess960.epd")).unwrap();
+    assert_eq!(entries.len(), 5);
+
+    for mut entry in entries {
+        for (depth, nodes) in entry.depths.iter().filter(|(_, nodes)| *nodes <= max_nodes) {
+            assert_eq!(perft(&mut entry.position, *depth), *nodes);
+        }
+    }
+}
+
+#[test]
+fn chess960_perft_suite() {
+    check_chess960_perft_suite(20_000);
+}
+
+// every depth, too slow without optimizations: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn chess960_perft_suite_full() {
+    check_chess960_perft_suite(u64::MAX);
+}
@@ -0,0 +1,16 @@
+use jaingo_unchaind_rust::{chess960, perft::parse_perft_suite, position::Position};
+
+// the perft suite positions and a spread of Chess960 starts, to check notations against
+pub fn sample_positions() -> Vec<Position> {
+    let mut positions: Vec<Position> = parse_perft_suite(include_str!("../data/perftsuite.epd"))
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.position)
+        .collect();
+    positions.extend(
+        (0..960)
+            .step_by(31)
+            .map(|id| chess960::start_position(id).unwrap()),
+    );
+    return positions;
+}
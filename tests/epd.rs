@@ -0,0 +1,178 @@
+use jaingo_unchaind_rust::{
+    epd::{parse_epd, Epd, EpdError},
+    fen_parser::{parse_fen, INITIAL_POSITION},
+};
+
+#[test]
+fn test_suite_records_round_trip() {
+    for record in [
+        "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+        "1kr5/3n4/q3p2p/p2n2p1/PppB1P2/5BP1/1P2Q2P/3R2K1 w - - bm f5; id \"STS(v1.0) Undermine.001\"; c0 \"f5=10, Be5+=2, Bf2=3, Bg4=2\";",
+        "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - am Qxf7+; ce -30; acd 12; pv Qf3 d5;",
+    ] {
+        assert_eq!(parse_epd(record).unwrap().to_string(), record);
+    }
+}
+
+#[test]
+fn quoted_operands() {
+    let epd =
+        parse_epd("8/8/8/8/8/8/8/K1k5 w - - id \"semi; colon\"; c0 \"say \\\"hi\\\"\"; c1 \"\";")
+            .unwrap();
+    assert_eq!(epd.id(), Some("semi; colon"));
+    assert_eq!(epd.comment(0), Some("say \"hi\""));
+    assert_eq!(epd.comment(1), Some(""));
+    assert_eq!(epd.comment(2), None);
+    assert_eq!(parse_epd(&epd.to_string()), Ok(epd));
+}
+
+#[test]
+fn move_operations() {
+    let epd = parse_epd(
+        "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - bm Qxf7#; am Qxe5+ Qh4; pv Qxf7#",
+    )
+    .unwrap();
+    let best = epd.best_moves().unwrap();
+    assert_eq!(best.len(), 1);
+    assert_eq!(epd.position.to_san(best[0]), "Qxf7#");
+    assert_eq!(epd.avoid_moves().unwrap().len(), 2);
+    assert_eq!(epd.principal_variation().unwrap(), best);
+
+    let opening =
+        parse_epd("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - pv e4 e5 Nf3 Nc6; bm Nf4;")
+            .unwrap();
+    assert_eq!(opening.principal_variation().unwrap().len(), 4);
+    assert!(opening.best_moves().is_err());
+    assert_eq!(opening.avoid_moves(), Ok(Vec::new()));
+}
+
+#[test]
+fn move_clocks() {
+    let epd = parse_epd("4k3/8/8/8/8/8/8/4K3 b - - hmvc 12; fmvn 40;").unwrap();
+    assert_eq!(epd.position.half_move_clock, 12);
+    assert_eq!(epd.position.full_move_number, 40);
+
+    // the clocks of perft suite records are written as operations
+    let record = "4k3/8/8/8/8/8/8/4K2R w K - 3 7 ;D1 15 ;D2 66";
+    let epd = parse_epd(record).unwrap();
+    assert_eq!(epd.position.half_move_clock, 3);
+    assert_eq!(epd.position.full_move_number, 7);
+    assert_eq!(epd.operation("D2"), Some(&[String::from("66")][..]));
+    let written = epd.to_string();
+    assert_eq!(
+        written,
+        "4k3/8/8/8/8/8/8/4K2R w K - D1 15; D2 66; hmvc 3; fmvn 7;"
+    );
+    let read_back = parse_epd(&written).unwrap();
+    assert_eq!(read_back.position, epd.position);
+    assert_eq!(read_back.to_string(), written);
+
+    // clocks changed on the position update their operations or add them
+    let mut epd = parse_epd("4k3/8/8/8/8/8/8/4K3 w - - fmvn 3; bm Kd2;").unwrap();
+    epd.position.full_move_number = 4;
+    assert_eq!(epd.to_string(), "4k3/8/8/8/8/8/8/4K3 w - - fmvn 4; bm Kd2;");
+    epd.position.half_move_clock = 5;
+    assert_eq!(
+        epd.to_string(),
+        "4k3/8/8/8/8/8/8/4K3 w - - fmvn 4; bm Kd2; hmvc 5;"
+    );
+    assert_eq!(parse_epd(&epd.to_string()).unwrap().position, epd.position);
+}
+
+#[test]
+fn editing_operations() {
+    let mut epd = Epd::new(parse_fen(INITIAL_POSITION).unwrap());
+    epd.set_operation("id", vec![String::from("start")])
+        .unwrap();
+    epd.set_operation("acd", vec![String::from("20")]).unwrap();
+    epd.set_operation("ce", vec![String::from("15")]).unwrap();
+    epd.set_operation("id", vec![String::from("initial position")])
+        .unwrap();
+    epd.set_operation("fmvn", vec![String::from("1")]).unwrap();
+    assert_eq!(
+        epd.to_string(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - id \"initial position\"; acd 20; ce 15; fmvn 1;"
+    );
+    assert_eq!(epd.analysis_depth(), Some(20));
+    assert_eq!(epd.centipawn_evaluation(), Some(15));
+
+    assert_eq!(epd.remove_operation("acd"), Some(vec![String::from("20")]));
+    assert_eq!(epd.remove_operation("acd"), None);
+    assert_eq!(epd.analysis_depth(), None);
+    assert_eq!(
+        epd.operations()
+            .iter()
+            .map(|(opcode, _)| opcode.as_str())
+            .collect::<Vec<&str>>(),
+        ["id", "ce", "fmvn"]
+    );
+}
+
+#[test]
+fn malformed_records() {
+    assert_eq!(
+        parse_epd("8/8/8/8/8/8/8/K1k5 w -"),
+        Err(EpdError::MissingFields(3))
+    );
+    assert!(matches!(
+        parse_epd("8/8/8/8/8/8/8/K1k5 x - - bm Ka2;"),
+        Err(EpdError::InvalidPosition(_))
+    ));
+    assert_eq!(
+        parse_epd("8/8/8/8/8/8/8/K1k5 w - - id \"open;"),
+        Err(EpdError::UnterminatedString { offset: 28 })
+    );
+    assert_eq!(
+        parse_epd("8/8/8/8/8/8/8/K1k5 w - - bm Ka2; 1x 2;"),
+        Err(EpdError::InvalidOpcode { offset: 33 })
+    );
+    assert_eq!(
+        parse_epd("8/8/8/8/8/8/8/K1k5 w - - bm Ka2; bm Kb2;"),
+        Err(EpdError::DuplicateOpcode { offset: 33 })
+    );
+    assert_eq!(
+        parse_epd("8/8/8/8/8/8/8/K1k5 w - - hmvc ten;"),
+        Err(EpdError::InvalidClock { offset: 30 })
+    );
+}
+
+#[test]
+fn rejected_edits_leave_the_record_unchanged() {
+    let mut epd = parse_epd("4k3/8/8/8/8/8/8/4K3 w - - hmvc 4; id \"kings\";").unwrap();
+    let before = epd.clone();
+    let operands = |words: &[&str]| -> Vec<String> {
+        return words.iter().map(|word| word.to_string()).collect();
+    };
+
+    let invalid = |opcode: &str, operand: Option<usize>| {
+        return Err(EpdError::InvalidOperation {
+            opcode: opcode.to_string(),
+            operand,
+        });
+    };
+    assert_eq!(
+        epd.set_operation("hmvc", operands(&["x"])),
+        invalid("hmvc", Some(0))
+    );
+    assert_eq!(
+        epd.set_operation("fmvn", operands(&["3", "4"])),
+        invalid("fmvn", Some(1))
+    );
+    assert_eq!(
+        epd.set_operation("hmvc", Vec::new()),
+        invalid("hmvc", Some(0))
+    );
+    for opcode in ["", "1x", "b m", "bm;", "\"id\""] {
+        assert_eq!(
+            epd.set_operation(opcode, operands(&["Ke2"])),
+            invalid(opcode, None)
+        );
+    }
+    assert_eq!(epd, before);
+
+    // whatever is accepted reads back the same
+    epd.set_operation("c0", operands(&["a \"quoted\"; comment"]))
+        .unwrap();
+    epd.set_operation("pv_2", operands(&["", "Ke2"])).unwrap();
+    assert_eq!(parse_epd(&epd.to_string()), Ok(epd));
+}
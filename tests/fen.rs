@@ -0,0 +1,210 @@
+use jaingo_unchaind_rust::{
+    fen_parser::{parse_fen, parse_fen_lenient, FenError, INITIAL_POSITION},
+    move_generator::generate_all_moves,
+    perft::parse_perft_suite,
+    position::Position,
+};
+
+fn corpus() -> Vec<Position> {
+    let mut positions: Vec<Position> = parse_perft_suite(include_str!("data/perftsuite.epd"))
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.position)
+        .collect();
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "8/8/8/8/8/8/8/K6k b - - 99 150",
+        "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 12 40",
+    ] {
+        positions.push(parse_fen(fen).unwrap());
+    }
+    return positions;
+}
+
+#[test]
+fn to_fen_writes_back_the_parsed_fen() {
+    let fens = [
+        INITIAL_POSITION,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "8/8/8/8/8/8/8/K6k b - - 99 150",
+        "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 12 40",
+    ];
+    for fen in fens {
+        let position = parse_fen(fen).unwrap();
+        assert_eq!(position.to_fen(), fen);
+        assert_eq!(position.fen().to_string(), fen);
+    }
+}
+
+#[test]
+fn fen_round_trips_over_the_corpus() {
+    for position in corpus() {
+        assert_eq!(parse_fen(&position.to_fen()).unwrap(), position);
+    }
+}
+
+// xorshift, so the random games are the same on every run
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    return *state;
+}
+
+#[test]
+fn fen_round_trips_along_random_games() {
+    let mut random_state = 0x2545f4914f6cdd1d;
+    for start in corpus() {
+        for _ in 0..4 {
+            let mut position = start.clone();
+            for _ in 0..40 {
+                let moves = generate_all_moves(&position);
+                if moves.is_empty() {
+                    break;
+                }
+                let mv = moves[(next_random(&mut random_state) % moves.len() as u64) as usize];
+                position.make_move(mv);
+
+                let fen = position.to_fen();
+                assert_eq!(parse_fen(&fen).unwrap(), position, "{}", fen);
+            }
+        }
+    }
+}
+
+fn error(fen: &str) -> FenError {
+    return parse_fen(fen).unwrap_err();
+}
+
+#[test]
+fn errors_locate_the_bad_character() {
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6k w - -"),
+        FenError::ComponentCount(4)
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6x w - - 0 1"),
+        FenError::UnexpectedCharacter {
+            component: 0,
+            offset: 16,
+            character: 'x'
+        }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K7k w - - 0 1"),
+        FenError::RankLength {
+            rank: 1,
+            offset: 16
+        }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K5k w - - 0 1"),
+        FenError::RankLength {
+            rank: 1,
+            offset: 17
+        }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/K6k w - - 0 1"),
+        FenError::RankCount { offset: 15 }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/8/K6k w - - 0 1"),
+        FenError::RankCount { offset: 15 }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6k x - - 0 1"),
+        FenError::UnexpectedCharacter {
+            component: 1,
+            offset: 18,
+            character: 'x'
+        }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6k w KK - 0 1"),
+        FenError::UnexpectedCharacter {
+            component: 2,
+            offset: 21,
+            character: 'K'
+        }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6k w - - 0 99999"),
+        FenError::InvalidNumber {
+            component: 5,
+            offset: 26
+        }
+    );
+
+    let err = error("8/8/8/8/8/8/8/K6k w - - -1 1");
+    assert_eq!((err.component(), err.offset()), (Some(4), Some(24)));
+}
+
+#[test]
+fn malformed_en_passant_targets_are_errors() {
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6k w - é3 0 1"),
+        FenError::UnexpectedCharacter {
+            component: 3,
+            offset: 22,
+            character: 'é'
+        }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6k w - e 0 1"),
+        FenError::Incomplete {
+            component: 3,
+            offset: 23
+        }
+    );
+    assert_eq!(
+        error("8/8/8/8/8/8/8/K6k w - e9 0 1"),
+        FenError::UnexpectedCharacter {
+            component: 3,
+            offset: 23,
+            character: '9'
+        }
+    );
+    assert!(parse_fen("8/8/8/8/8/8/8/K6k w - e3é 0 1").is_err());
+}
+
+#[test]
+fn surplus_whitespace_is_accepted() {
+    let expected = parse_fen("8/8/8/8/8/8/8/K6k b - - 0 1").unwrap();
+    for fen in [
+        "8/8/8/8/8/8/8/K6k b - - 0 1 ",
+        "  8/8/8/8/8/8/8/K6k  b\t- -  0 1\n",
+    ] {
+        assert_eq!(parse_fen(fen), Ok(expected.clone()), "{:?}", fen);
+        assert_eq!(parse_fen_lenient(fen), Ok(expected.clone()), "{:?}", fen);
+    }
+    // offsets still count from the start of the text
+    assert_eq!(
+        error("  8/8/8/8/8/8/8/K6k x - - 0 1"),
+        FenError::UnexpectedCharacter {
+            component: 1,
+            offset: 20,
+            character: 'x'
+        }
+    );
+}
+
+#[test]
+fn lenient_parsing_accepts_missing_clocks() {
+    let expected = parse_fen("8/8/8/8/8/8/8/K6k b - - 0 1").unwrap();
+    for fen in [
+        "8/8/8/8/8/8/8/K6k b - -",
+        " 8/8/8/8/8/8/8/K6k b - - ",
+        "8/8/8/8/8/8/8/K6k b - - 0",
+    ] {
+        assert_eq!(parse_fen_lenient(fen), Ok(expected.clone()), "{:?}", fen);
+        assert!(parse_fen(fen).is_err(), "{:?}", fen);
+    }
+
+    assert_eq!(
+        parse_fen_lenient("8/8/8/8/8/8/8/K6k b -"),
+        Err(FenError::ComponentCount(3))
+    );
+}
@@ -0,0 +1,78 @@
+use jaingo_unchaind_rust::{
+    fen_parser::{parse_fen, INITIAL_POSITION},
+    game::Game,
+    move_generator::generate_all_moves,
+    moves::{Move, Square, QUIET_MOVE},
+    outcome::Outcome,
+};
+
+fn play(game: &mut Game, moves: &str) {
+    for uci in moves.split_whitespace() {
+        let mv = generate_all_moves(game.position())
+            .into_iter()
+            .find(|mv| mv.to_string() == uci)
+            .unwrap_or_else(|| panic!("{} is not legal", uci));
+        game.push(mv).unwrap();
+    }
+}
+
+#[test]
+fn push_and_pop_restore_the_positions() {
+    let mut game = Game::default();
+    assert_eq!(game.position(), &parse_fen(INITIAL_POSITION).unwrap());
+
+    play(&mut game, "e2e4 c7c5 g1f3 d7d6 e1e2");
+    assert_eq!(game.moves().len(), 5);
+    let positions: Vec<_> = game.positions().collect();
+    assert_eq!(positions.len(), 6);
+    assert_eq!(&positions[0], game.starting_position());
+    assert_eq!(&positions[5], game.position());
+
+    for expected in positions.iter().rev().skip(1) {
+        assert!(game.pop().is_some());
+        assert_eq!(game.position(), expected);
+    }
+    assert_eq!(game.pop(), None);
+    assert_eq!(game.positions().count(), 1);
+}
+
+#[test]
+fn push_rejects_illegal_moves() {
+    let mut game = Game::default();
+    let mv = Move::new(&Square(12), &Square(36), QUIET_MOVE);
+    assert!(game.push(mv).is_err());
+    assert!(game.moves().is_empty());
+    assert_eq!(game.position(), game.starting_position());
+}
+
+#[test]
+fn repetitions_reset_at_irreversible_moves() {
+    let mut game = Game::default();
+    assert_eq!(game.repetition_count(), 1);
+
+    play(&mut game, "g1f3 g8f6 f3g1 f6g8");
+    assert_eq!(game.repetition_count(), 2);
+    play(&mut game, "g1f3 g8f6 f3g1 f6g8");
+    assert_eq!(game.repetition_count(), 3);
+    assert_eq!(game.outcome(), Some(Outcome::ThreefoldRepetition));
+
+    // after a pawn move only the occurrences since then count
+    play(&mut game, "e2e4 e7e5");
+    assert_eq!(game.repetition_count(), 1);
+    play(&mut game, "g1f3 g8f6 f3g1 f6g8");
+    assert_eq!(game.repetition_count(), 2);
+    assert_eq!(game.outcome(), None);
+
+    game.pop();
+    assert_eq!(game.repetition_count(), 1);
+}
+
+#[test]
+fn outcome_of_a_finished_game() {
+    let mut game = Game::default();
+    play(&mut game, "f2f3 e7e5 g2g4 d8h4");
+    assert_eq!(
+        game.outcome().unwrap().to_string(),
+        "Black wins by checkmate"
+    );
+}
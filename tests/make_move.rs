@@ -0,0 +1,108 @@
+use jaingo_unchaind_rust::{
+    fen_parser::parse_fen,
+    move_generator::generate_all_moves,
+    moves::{Move, Square, CAPTURE, DOUBLE_PAWN_PUSH, KING_CASTLE},
+    piece::PieceType,
+    position::Color,
+};
+
+static FENS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1",
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+];
+
+#[test]
+fn unmake_move_restores_the_position() {
+    for fen in FENS {
+        let mut position = parse_fen(fen).unwrap();
+        let original = position.clone();
+
+        for mv in generate_all_moves(&original) {
+            let undo = position.make_move(mv);
+            assert_ne!(position, original, "{} did not change {}", mv, fen);
+            position.unmake_move(mv, undo);
+            assert_eq!(position, original, "{} was not undone in {}", mv, fen);
+        }
+    }
+}
+
+#[test]
+fn make_move_updates_clocks_and_en_passant_target() {
+    let mut position = parse_fen(FENS[0]).unwrap();
+
+    position.make_move(Move::new(&Square(12), &Square(28), DOUBLE_PAWN_PUSH));
+    assert_eq!(position.active_color, Color::Black);
+    assert_eq!(position.en_passant_target, Some(Square(20)));
+    assert_eq!(
+        (position.half_move_clock, position.full_move_number),
+        (0, 1)
+    );
+
+    position.make_move("g8f6".parse().unwrap());
+    assert_eq!(position.active_color, Color::White);
+    assert_eq!(position.en_passant_target, None);
+    assert_eq!(
+        (position.half_move_clock, position.full_move_number),
+        (1, 2)
+    );
+}
+
+#[test]
+fn make_move_revokes_castling_rights() {
+    let mut position = parse_fen(FENS[1]).unwrap();
+
+    // castling moves the rook and loses both rights
+    position.make_move(Move::new(&Square(4), &Square(6), KING_CASTLE));
+    assert_eq!(
+        position.piece_placement.piece_on(&Square(5)),
+        Some((Color::White, PieceType::Rook))
+    );
+    let castling_rights = position.castling_rights.as_ref().unwrap();
+    assert!(
+        !castling_rights.king_side(&Color::White) && !castling_rights.queen_side(&Color::White)
+    );
+
+    // capturing a rook on its home square removes the right of its owner
+    let mut position = parse_fen("r3k2r/8/8/8/8/8/6B1/R3K2R w KQkq - 0 1").unwrap();
+    position.make_move(Move::new(&Square(14), &Square(56), CAPTURE));
+    let castling_rights = position.castling_rights.as_ref().unwrap();
+    assert!(castling_rights.king_side(&Color::Black) && !castling_rights.queen_side(&Color::Black));
+    assert!(castling_rights.king_side(&Color::White) && castling_rights.queen_side(&Color::White));
+}
+
+#[test]
+fn null_move_passes_the_turn() {
+    let mut position = parse_fen(FENS[3]).unwrap();
+    let original = position.clone();
+
+    let undo = position.make_null_move().unwrap();
+    assert_eq!(position.active_color, Color::Black);
+    assert_eq!(position.en_passant_target, None);
+    assert_eq!(
+        (position.half_move_clock, position.full_move_number),
+        (1, 3)
+    );
+    assert_eq!(position.hash, position.compute_hash());
+    assert_ne!(position.hash, original.hash);
+
+    // a null move of each side is a transposition of the position without the target
+    let second_undo = position.make_null_move().unwrap();
+    let mut without_target = original.clone();
+    without_target.en_passant_target = None;
+    assert_eq!(position.hash, without_target.compute_hash());
+    assert_eq!(position.full_move_number, 4);
+
+    position.unmake_null_move(second_undo);
+    position.unmake_null_move(undo);
+    assert_eq!(position, original);
+}
+
+#[test]
+fn null_move_is_refused_in_check() {
+    let mut position = parse_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+    let original = position.clone();
+    assert_eq!(position.make_null_move(), None);
+    assert_eq!(position, original);
+}
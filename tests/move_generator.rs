@@ -0,0 +1,114 @@
+use jaingo_unchaind_rust::{
+    fen_parser::parse_fen,
+    move_generator::generate_all_moves,
+    moves::{
+        Move, Square, CAPTURE, EN_PASSANT_CAPTURE, KING_CASTLE, PROMOTION, QUEEN_CASTLE, QUIET_MOVE,
+    },
+};
+
+fn square(name: &str) -> Square {
+    return name.parse::<Square>().unwrap();
+}
+
+// the legal moves of the piece on the square, sorted
+fn moves_from(fen: &str, from: &str) -> Vec<Move> {
+    let from = square(from);
+    let mut moves: Vec<Move> = generate_all_moves(&parse_fen(fen).unwrap())
+        .into_iter()
+        .filter(|mv| mv.from() == from)
+        .collect();
+    moves.sort_by_key(|mv| mv.to_string());
+    return moves;
+}
+
+fn quiet_moves(from: &str, targets: &[&str]) -> Vec<Move> {
+    let mut moves: Vec<Move> = targets
+        .iter()
+        .map(|to| Move::new(&square(from), &square(to), QUIET_MOVE))
+        .collect();
+    moves.sort_by_key(|mv| mv.to_string());
+    return moves;
+}
+
+#[test]
+fn pinned_pieces_stay_on_the_pin_line() {
+    // a pinned knight can never move
+    assert!(moves_from("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1", "e2").is_empty());
+
+    let mut rook = quiet_moves("e2", &["e3", "e4", "e5", "e6"]);
+    rook.push(Move::new(&square("e2"), &square("e7"), CAPTURE));
+    rook.sort_by_key(|mv| mv.to_string());
+    assert_eq!(moves_from("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1", "e2"), rook);
+
+    let mut bishop = quiet_moves("d2", &["c3", "b4"]);
+    bishop.push(Move::new(&square("d2"), &square("a5"), CAPTURE));
+    bishop.sort_by_key(|mv| mv.to_string());
+    assert_eq!(moves_from("4k3/8/8/b7/8/8/3B4/4K3 w - - 0 1", "d2"), bishop);
+}
+
+#[test]
+fn only_the_king_moves_in_double_check() {
+    // rook and knight both give check, the queen could take the knight but that is not enough
+    let fen = "4k3/8/8/8/8/5n1Q/8/r3K3 w - - 0 1";
+    let moves = generate_all_moves(&parse_fen(fen).unwrap());
+    // the king can't step along the rook's ray, not even to the square it shields
+    assert_eq!(moves.len(), 2);
+    assert_eq!(moves_from(fen, "e1"), quiet_moves("e1", &["e2", "f2"]));
+}
+
+#[test]
+fn castling_through_attacked_squares() {
+    let castles = |fen: &str| -> Vec<u16> {
+        let mut flags: Vec<u16> = moves_from(fen, "e1")
+            .iter()
+            .map(|mv| mv.flags())
+            .filter(|flags| *flags == KING_CASTLE || *flags == QUEEN_CASTLE)
+            .collect();
+        flags.sort();
+        return flags;
+    };
+
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+        vec![KING_CASTLE, QUEEN_CASTLE]
+    );
+    // the knight covers f1 and d1, squares the king crosses
+    assert!(castles("r3k2r/8/8/8/8/4n3/8/R3K2R w KQkq - 0 1").is_empty());
+    // b1 only has to be empty, the king doesn't cross it
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/p7/R3K2R w KQkq - 0 1"),
+        vec![KING_CASTLE, QUEEN_CASTLE]
+    );
+    // no castling out of check
+    assert!(castles("r3k2r/8/8/8/8/3n4/8/R3K2R w KQkq - 0 1").is_empty());
+    // or into it
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/7p/R3K2R w KQkq - 0 1"),
+        vec![QUEEN_CASTLE]
+    );
+}
+
+#[test]
+fn en_passant_can_not_uncover_the_king_along_the_rank() {
+    let en_passant = Move::new(&square("e5"), &square("d6"), EN_PASSANT_CAPTURE);
+    assert!(moves_from("8/8/8/K2pP3/8/8/8/7k w - d6 0 1", "e5").contains(&en_passant));
+    // both pawns leave the fifth rank, which would open it for the rook
+    assert!(!moves_from("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1", "e5").contains(&en_passant));
+}
+
+#[test]
+fn every_promotion_piece_is_generated() {
+    let moves = moves_from("r1n1k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7");
+    let mut expected: Vec<Move> = Vec::new();
+    for (to, capture_flag) in [("a8", CAPTURE), ("b8", QUIET_MOVE), ("c8", CAPTURE)] {
+        for piece_offset in 0..4 {
+            expected.push(Move::new(
+                &square("b7"),
+                &square(to),
+                PROMOTION | capture_flag | piece_offset,
+            ));
+        }
+    }
+    expected.sort_by_key(|mv| mv.to_string());
+    assert_eq!(moves, expected);
+}
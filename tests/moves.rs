@@ -0,0 +1,93 @@
+use jaingo_unchaind_rust::{
+    moves::{Move, Square, CAPTURE, EN_PASSANT_CAPTURE, KING_CASTLE, PROMOTION, QUIET_MOVE},
+    piece::PieceType,
+};
+
+fn square(name: &str) -> Square {
+    return name.parse::<Square>().unwrap();
+}
+
+#[test]
+fn from_to_and_flags_round_trip() {
+    for from in 0..64 {
+        for to in 0..64 {
+            for flags in 0..16 {
+                let mv = Move::new(&Square(from), &Square(to), flags);
+                assert_eq!(mv.from(), Square(from));
+                assert_eq!(mv.to(), Square(to));
+                assert_eq!(mv.flags(), flags);
+            }
+        }
+    }
+}
+
+#[test]
+fn flag_queries() {
+    let (e7, e8) = (square("e7"), square("e8"));
+    assert!(Move::new(&e7, &e8, CAPTURE).is_capture());
+    assert!(Move::new(&e7, &e8, EN_PASSANT_CAPTURE).is_capture());
+    assert!(Move::new(&e7, &e8, EN_PASSANT_CAPTURE).is_en_passant());
+    assert!(Move::new(&e7, &e8, KING_CASTLE).is_castle());
+    assert!(!Move::new(&e7, &e8, QUIET_MOVE).is_capture());
+    assert_eq!(Move::new(&e7, &e8, CAPTURE).promotion_piece(), None);
+}
+
+#[test]
+fn null_move() {
+    assert!(Move::NULL.is_null());
+    assert_eq!(Move::NULL.to_string(), "0000");
+    assert_eq!("0000".parse::<Move>(), Ok(Move::NULL));
+    assert!(!Move::new(&square("a1"), &square("a2"), QUIET_MOVE).is_null());
+}
+
+#[test]
+fn promotions_with_and_without_capture() {
+    let (from, to) = (square("b7"), square("a8"));
+    for (piece, piece_char) in [
+        (PieceType::Knight, 'n'),
+        (PieceType::Bishop, 'b'),
+        (PieceType::Rook, 'r'),
+        (PieceType::Queen, 'q'),
+    ] {
+        for is_capture in [false, true] {
+            let mv = Move::promotion(&from, &to, &piece, is_capture);
+            assert_eq!((mv.from(), mv.to()), (from, to));
+            assert!(mv.is_promotion());
+            assert_eq!(mv.flags() & PROMOTION, PROMOTION);
+            assert_eq!(mv.is_capture(), is_capture);
+            assert_eq!(mv.promotion_piece(), Some(piece));
+            assert_eq!(mv.to_string(), format!("b7a8{}", piece_char));
+        }
+
+        // the text has no capture flag, so promotions are read back as quiet ones
+        let text = format!("b7a8{}", piece_char);
+        assert_eq!(
+            text.parse::<Move>(),
+            Ok(Move::promotion(&from, &to, &piece, false))
+        );
+    }
+}
+
+#[test]
+fn text_round_trips() {
+    for from in 0..64 {
+        for to in 0..64 {
+            let mv = Move::new(&Square(from), &Square(to), QUIET_MOVE);
+            if mv.is_null() {
+                continue;
+            }
+            let text = mv.to_string();
+            assert_eq!(text, format!("{}{}", Square(from), Square(to)));
+            assert_eq!(text.parse::<Move>(), Ok(mv));
+        }
+    }
+    assert_eq!("e2e4".parse::<Move>().unwrap().to_string(), "e2e4");
+    assert_eq!("e7e8Q".parse::<Move>().unwrap().to_string(), "e7e8q");
+
+    for text in [
+        "", "e2", "e2e", "000", "00000", "e2e4qq", "e7e8k", "e7e8p", "e7e8x", "e2e9", "i2e4",
+        "E2E4", "é2e4",
+    ] {
+        assert!(text.parse::<Move>().is_err(), "{}", text);
+    }
+}
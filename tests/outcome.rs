@@ -0,0 +1,95 @@
+use jaingo_unchaind_rust::{
+    fen_parser::parse_fen, move_generator::generate_all_moves, outcome::Outcome, position::Color,
+};
+
+fn outcome(fen: &str) -> Option<Outcome> {
+    return parse_fen(fen).unwrap().outcome(&[]);
+}
+
+#[test]
+fn checkmate_and_stalemate() {
+    assert_eq!(
+        outcome("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"),
+        Some(Outcome::Checkmate(Color::Black))
+    );
+    assert_eq!(
+        outcome("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"),
+        Some(Outcome::Stalemate)
+    );
+    assert_eq!(
+        outcome("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        None
+    );
+    // checkmate takes precedence over the move rules
+    assert_eq!(
+        outcome("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 150 80"),
+        Some(Outcome::Checkmate(Color::Black))
+    );
+}
+
+#[test]
+fn insufficient_material() {
+    for fen in [
+        "8/8/4k3/8/8/8/4K3/8 w - - 0 1",
+        "8/8/4k3/8/8/2B5/4K3/8 w - - 0 1",
+        "8/8/4k3/8/8/2N5/4K3/8 b - - 0 1",
+        "8/2b5/4k3/8/8/2B5/4K3/8 w - - 0 1",
+        "8/1b6/b3k3/8/8/3B4/4K3/8 w - - 0 1",
+    ] {
+        assert_eq!(outcome(fen), Some(Outcome::InsufficientMaterial), "{}", fen);
+    }
+
+    for fen in [
+        "8/8/2n1k3/8/8/2N5/4K3/8 w - - 0 1",
+        "8/3b4/4k3/8/8/2B5/4K3/8 w - - 0 1",
+        "8/8/4k3/8/8/2NN4/4K3/8 w - - 0 1",
+        "8/8/4k3/8/8/2P5/4K3/8 w - - 0 1",
+    ] {
+        assert_eq!(outcome(fen), None, "{}", fen);
+    }
+}
+
+#[test]
+fn move_rules() {
+    let fifty = outcome("8/8/4k3/8/8/2R5/4K3/8 w - - 100 80").unwrap();
+    assert_eq!(fifty, Outcome::FiftyMoveRule);
+    assert!(fifty.is_claimable());
+
+    let seventy_five = outcome("8/8/4k3/8/8/2R5/4K3/8 w - - 150 100").unwrap();
+    assert_eq!(seventy_five, Outcome::SeventyFiveMoveRule);
+    assert!(!seventy_five.is_claimable());
+}
+
+#[test]
+fn repetitions() {
+    let mut position =
+        parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let mut previous_hashes: Vec<u64> = Vec::new();
+
+    for (ply, uci) in ["g1f3", "g8f6", "f3g1", "f6g8"]
+        .iter()
+        .cycle()
+        .take(16)
+        .enumerate()
+    {
+        let mv = generate_all_moves(&position)
+            .into_iter()
+            .find(|mv| mv.to_string() == *uci)
+            .unwrap();
+        previous_hashes.push(position.hash);
+        position.make_move(mv);
+
+        let expected = match ply + 1 {
+            8..=15 => Some(Outcome::ThreefoldRepetition),
+            16 => Some(Outcome::FivefoldRepetition),
+            _ => None,
+        };
+        assert_eq!(
+            position.outcome(&previous_hashes),
+            expected,
+            "ply {}",
+            ply + 1
+        );
+    }
+    assert_eq!(position.repetition_count(&previous_hashes), 5);
+}
@@ -0,0 +1,119 @@
+use jaingo_unchaind_rust::{
+    fen_parser::parse_fen,
+    perft::{parse_perft_suite, parse_perft_suite_line, perft, perft_divide},
+};
+
+// https://www.chessprogramming.org/Perft_Results
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let mut position = parse_fen(fen).unwrap();
+    for (depth, nodes) in expected.iter().enumerate() {
+        assert_eq!(
+            perft(&mut position, depth as u32 + 1),
+            *nodes,
+            "depth {} of {}",
+            depth + 1,
+            fen
+        );
+    }
+}
+
+#[test]
+fn perft_initial_position() {
+    assert_perft(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        &[20, 400, 8902, 197281],
+    );
+}
+
+#[test]
+fn perft_kiwipete() {
+    assert_perft(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        &[48, 2039, 97862],
+    );
+}
+
+#[test]
+fn perft_position_3() {
+    assert_perft(
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        &[14, 191, 2812, 43238],
+    );
+}
+
+#[test]
+fn perft_position_4() {
+    assert_perft(
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        &[6, 264, 9467],
+    );
+    // mirrored
+    assert_perft(
+        "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+        &[6, 264, 9467],
+    );
+}
+
+#[test]
+fn perft_position_5() {
+    assert_perft(
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        &[44, 1486, 62379],
+    );
+}
+
+#[test]
+fn perft_position_6() {
+    assert_perft(
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        &[46, 2079, 89890],
+    );
+}
+
+#[test]
+fn perft_divide_sums_to_perft() {
+    let mut position =
+        parse_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+    let divide = perft_divide(&mut position, 2);
+
+    assert_eq!(divide.len(), 48);
+    assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2039);
+    assert!(divide
+        .iter()
+        .any(|(mv, nodes)| mv.to_string() == "e1g1" && *nodes == 43));
+}
+
+// checks every depth of the suite with at most max_nodes nodes
+fn check_perft_suite(max_nodes: u64) {
+    let entries = parse_perft_suite(include_str!("data/perftsuite.epd")).unwrap();
+    assert_eq!(entries.len(), 14);
+
+    for mut entry in entries {
+        for (depth, nodes) in entry.depths.iter().filter(|(_, nodes)| *nodes <= max_nodes) {
+            assert_eq!(perft(&mut entry.position, *depth), *nodes);
+        }
+    }
+}
+
+#[test]
+fn perft_suite() {
+    check_perft_suite(20_000);
+}
+
+// every depth, too slow without optimizations: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn perft_suite_full() {
+    check_perft_suite(u64::MAX);
+}
+
+#[test]
+fn perft_suite_line_without_clocks() {
+    let entry = parse_perft_suite_line("4k3/8/8/8/8/8/8/4K2R w K - ;D1 15 ;D2 66").unwrap();
+    assert_eq!(entry.depths, vec![(1, 15), (2, 66)]);
+
+    assert!(parse_perft_suite_line("4k3/8/8/8/8/8/8/4K2R w K - ;X1 15").is_err());
+    assert!(parse_perft_suite_line("4k3/8/8/8/8/8/8/4K2R w K - ;D1 fifteen").is_err());
+    assert!(parse_perft_suite_line("4k3/8/8/8/8/8/8/4K2R w K - ;D1 15 16").is_err());
+    assert!(parse_perft_suite_line("4k3/8/8/8/8/8/8/4K2R w K - ;D1 15 ;D1 15").is_err());
+}
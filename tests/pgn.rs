@@ -0,0 +1,348 @@
+use jaingo_unchaind_rust::{
+    chess960,
+    fen_parser::parse_fen,
+    moves::Move,
+    pgn::{GameNode, GameResult, PgnError, PgnGame, PgnReader, PgnWriteOptions},
+    position::{Color, Position},
+    san::SanError,
+};
+
+fn read_games(pgn: &str) -> Vec<Result<PgnGame, PgnError>> {
+    return PgnReader::new(pgn.as_bytes()).collect();
+}
+
+fn read_game(pgn: &str) -> PgnGame {
+    let mut games = read_games(pgn);
+    assert_eq!(games.len(), 1);
+    return games.remove(0).unwrap();
+}
+
+// the SAN of the moves of a line, played from the position
+fn sans(position: &Position, moves: &[Move]) -> Vec<String> {
+    let mut position = position.clone();
+    return moves
+        .iter()
+        .map(|mv| {
+            let san = position.to_san(*mv);
+            position.make_move(*mv);
+            san
+        })
+        .collect();
+}
+
+static ANNOTATED_GAME: &str = r#"[Event "Casual game"]
+[Site "Berlin GER"]
+[Date "1852.??.??"]
+[Round "?"]
+[White "Adolf Anderssen"]
+[Black "Jean Dufresne"]
+[Result "1-0"]
+[ECO "C52"]
+
+{Evergreen game} 1.e4 e5 2.Nf3 Nc6 3.Bc4 Bc5 4.b4!? Bxb4 5.c3 Ba5 6.d4 exd4 7.O-O
+d3 $6 (7...Nge7 {is more solid} 8.cxd4 (8.Ng5 d5) 8...d5) 8.Qb3 Qf6 9.e5 Qg6
+10.Re1 Nge7 11.Ba3 b5 12.Qxb5 Rb8 13.Qa4 Bb6 14.Nbd2 Bb7 15.Ne4 Qf5 16.Bxd3
+Qh5 17.Nf6+ gxf6 18.exf6 Rg8 19.Rad1 Qxf3 20.Rxe7+ Nxe7 21.Qxd7+ Kxd7 22.Bf5+
+Ke8 23.Bd7+ Kf8 24.Bxe7# 1-0
+"#;
+
+#[test]
+fn tags_moves_comments_nags_and_variations() {
+    let game = read_game(ANNOTATED_GAME);
+
+    assert_eq!(game.tags.len(), 8);
+    assert_eq!(
+        game.tags[0],
+        (String::from("Event"), String::from("Casual game"))
+    );
+    assert_eq!(game.tag("White"), Some("Adolf Anderssen"));
+    assert_eq!(game.tag("ECO"), Some("C52"));
+    assert_eq!(game.tag("Annotator"), None);
+    assert_eq!(game.result, GameResult::WhiteWins);
+    assert_eq!(game.comment.as_deref(), Some("Evergreen game"));
+
+    assert_eq!(game.main_line.len(), 47);
+    assert_eq!(game.main_line[6].nags, vec![5]);
+    let d3 = &game.main_line[13];
+    assert_eq!(d3.nags, vec![6]);
+    assert_eq!(d3.variations.len(), 1);
+
+    let variation = &d3.variations[0];
+    let after_castling = game.main_line_game().positions().nth(13).unwrap();
+    let variation_moves: Vec<Move> = variation.iter().map(|node| node.mv).collect();
+    assert_eq!(
+        sans(&after_castling, &variation_moves),
+        vec!["Nge7", "cxd4", "d5"]
+    );
+    assert_eq!(variation[0].comment.as_deref(), Some("is more solid"));
+    assert_eq!(variation[1].variations.len(), 1);
+    assert_eq!(variation[1].variations[0].len(), 2);
+
+    let game = game.main_line_game();
+    assert_eq!(game.moves().len(), 47);
+    assert!(game.outcome().is_some());
+}
+
+#[test]
+fn games_are_streamed_and_errors_stay_in_their_game() {
+    let pgn = r#"[Event "first"]
+
+1. e4 e5 2. Nf3 *
+
+[Event "illegal move"]
+
+1. e4 e5 2. Ke3 Nc6 1-0
+
+[Event "third"]
+
+1. d4 {a comment
+over two lines} d5 0-1
+"#;
+    let games = read_games(pgn);
+    assert_eq!(games.len(), 3);
+
+    let first = games[0].as_ref().unwrap();
+    assert_eq!(first.tag("Event"), Some("first"));
+    assert_eq!(first.main_line.len(), 3);
+    assert_eq!(first.result, GameResult::Unknown);
+
+    match &games[1] {
+        Err(PgnError::InvalidMove { line, error }) => {
+            assert_eq!(*line, 7);
+            assert_eq!(*error, SanError::IllegalMove(String::from("Ke3")));
+        }
+        other => panic!("expected an illegal move, got {:?}", other),
+    }
+
+    let third = games[2].as_ref().unwrap();
+    assert_eq!(third.tag("Event"), Some("third"));
+    assert_eq!(third.result, GameResult::BlackWins);
+    assert_eq!(
+        third.main_line[0].comment.as_deref(),
+        Some("a comment over two lines")
+    );
+}
+
+#[test]
+fn fen_tag_and_black_to_move() {
+    let game = read_game(
+        r#"[FEN "4k3/8/8/8/8/8/4p3/4K3 b - - 0 40"]
+[SetUp "1"]
+
+40... Kd7 41. Kxe2 1/2-1/2"#,
+    );
+    assert_eq!(
+        game.starting_position,
+        parse_fen("4k3/8/8/8/8/8/4p3/4K3 b - - 0 40").unwrap()
+    );
+    assert_eq!(game.starting_position.active_color, Color::Black);
+    assert_eq!(game.main_line.len(), 2);
+    assert_eq!(game.result, GameResult::Draw);
+
+    let without_clocks = read_game("[FEN \"4k3/8/8/8/8/8/4p3/4K3 b - -\"]\n\n1... Kd7 *\n");
+    assert_eq!(
+        without_clocks.starting_position,
+        parse_fen("4k3/8/8/8/8/8/4p3/4K3 b - - 0 1").unwrap()
+    );
+    assert_eq!(without_clocks.main_line.len(), 1);
+
+    let games = read_games("[FEN \"8/8/8 w - - 0 1\"]\n\n*\n");
+    assert!(matches!(
+        games[0],
+        Err(PgnError::InvalidFen { line: 1, .. })
+    ));
+}
+
+#[test]
+fn lenient_movetext() {
+    // escaped lines, rest of line comments, glued move numbers, zeros for castling,
+    // no result token at the end of the input
+    let game = read_game(
+        "% exported by some tool\n[Result \"1-0\"]\n1.e4 e5 ; open game\n2.Nf3 Nc6 3.Bc4 Bc5 4.0-0 Nf6 5.d3!! ??\n",
+    );
+    assert_eq!(game.main_line.len(), 9);
+    assert_eq!(game.main_line[1].comment.as_deref(), Some("open game"));
+    assert!(game.main_line[6].mv.is_castle());
+    assert_eq!(game.main_line[8].nags, vec![3, 4]);
+    assert_eq!(game.result, GameResult::WhiteWins);
+
+    // movetext without any tags
+    let game = read_game("1. d4 d5 2. c4 *");
+    assert!(game.tags.is_empty());
+    assert_eq!(game.main_line.len(), 3);
+}
+
+// the error of the only game in the PGN
+fn read_error(pgn: &str) -> PgnError {
+    let mut games = read_games(pgn);
+    assert_eq!(games.len(), 1, "{}", pgn);
+    match games.remove(0) {
+        Err(error) => return error,
+        Ok(game) => panic!("{} was read as {:?}", pgn, game),
+    }
+}
+
+#[test]
+fn malformed_games() {
+    assert!(matches!(
+        read_error("[Event \"unclosed]\n1. e4 *"),
+        PgnError::InvalidTag { line: 1 }
+    ));
+    assert!(matches!(
+        read_error("1. e4 e5 2. Nf3 ) *"),
+        PgnError::UnexpectedToken { line: 1, .. }
+    ));
+    assert!(matches!(
+        read_error("( 1. e4 ) 1. d4 *"),
+        PgnError::UnexpectedToken { line: 1, .. }
+    ));
+    assert!(matches!(
+        read_error("1. e4 (1. d4 d5\n2. c4 *"),
+        PgnError::UnexpectedToken { line: 2, .. }
+    ));
+    assert!(matches!(
+        read_error("1. e4 {never closed\n\n"),
+        PgnError::UnterminatedComment { line: 1 }
+    ));
+    assert!(matches!(
+        read_error("1. e4 & e5 *"),
+        PgnError::UnexpectedCharacter {
+            line: 1,
+            character: '&'
+        }
+    ));
+    assert!(matches!(
+        read_error("1. e4 (1. d4 d5"),
+        PgnError::UnterminatedVariation { line: 1 }
+    ));
+}
+
+#[test]
+fn writes_export_format() {
+    let game = read_game(ANNOTATED_GAME);
+    let expected = r#"[Event "Casual game"]
+[Site "Berlin GER"]
+[Date "1852.??.??"]
+[Round "?"]
+[White "Adolf Anderssen"]
+[Black "Jean Dufresne"]
+[Result "1-0"]
+[ECO "C52"]
+
+{Evergreen game} 1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. b4 $5 Bxb4 5. c3 Ba5 6. d4
+exd4 7. O-O d3 $6 (7... Nge7 {is more solid} 8. cxd4 (8. Ng5 d5) 8... d5) 8. Qb3
+Qf6 9. e5 Qg6 10. Re1 Nge7 11. Ba3 b5 12. Qxb5 Rb8 13. Qa4 Bb6 14. Nbd2 Bb7 15.
+Ne4 Qf5 16. Bxd3 Qh5 17. Nf6+ gxf6 18. exf6 Rg8 19. Rad1 Qxf3 20. Rxe7+ Nxe7 21.
+Qxd7+ Kxd7 22. Bf5+ Ke8 23. Bd7+ Kf8 24. Bxe7# 1-0
+"#;
+    assert_eq!(game.to_string(), expected);
+    assert_eq!(read_game(expected), game);
+}
+
+#[test]
+fn closing_braces_are_left_out_of_comments() {
+    let mut game = read_game("1. e4 *");
+    game.comment = Some(String::from("{nested}"));
+    game.main_line[0].comment = Some(String::from("x}y"));
+    let pgn = game.to_string();
+    assert!(pgn.ends_with("\n{{nested} 1. e4 {xy} *\n"), "{}", pgn);
+
+    let written = read_game(&pgn);
+    assert_eq!(written.comment.as_deref(), Some("{nested"));
+    assert_eq!(written.main_line[0].comment.as_deref(), Some("xy"));
+}
+
+#[test]
+fn strips_comments_and_variations() {
+    let game = read_game(ANNOTATED_GAME);
+    let no_comments = game.to_pgn(&PgnWriteOptions {
+        comments: false,
+        variations: true,
+    });
+    let movetext = no_comments
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    assert!(no_comments.contains("\n1. e4 e5"));
+    assert!(movetext.contains("d3 $6 (7... Nge7 8. cxd4 (8. Ng5 d5) 8... d5) 8. Qb3"));
+    assert!(!no_comments.contains('{'));
+
+    let main_line = game.to_pgn(&PgnWriteOptions {
+        comments: false,
+        variations: false,
+    });
+    let movetext = main_line
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    assert!(movetext.contains("7. O-O d3 $6 8. Qb3"));
+    assert!(!main_line.contains('('));
+
+    let reread = read_game(&main_line);
+    assert_eq!(reread.main_line_game(), game.main_line_game());
+    assert!(reread
+        .main_line
+        .iter()
+        .all(|node| node.variations.is_empty()));
+}
+
+#[test]
+fn setup_tags_and_move_numbers() {
+    let mut game = PgnGame::new(parse_fen("4k3/8/8/8/8/8/4p3/4K3 b - - 0 40").unwrap());
+    game.tags
+        .push((String::from("White"), String::from("Quote \"\\ me")));
+    game.tags.push((String::from("FEN"), String::from("stale")));
+    let position = game.starting_position.clone();
+    let mut kd7 = GameNode::new(position.parse_san("Kd7").unwrap());
+    kd7.comment = Some(String::from(
+        "a long comment that has to be wrapped because it does not fit into a single line of the movetext",
+    ));
+    game.main_line.push(kd7);
+    let mut after_kd7 = position.clone();
+    after_kd7.make_move(game.main_line[0].mv);
+    game.main_line
+        .push(GameNode::new(after_kd7.parse_san("Kxe2").unwrap()));
+    game.result = GameResult::Draw;
+
+    let pgn = game.to_string();
+    let (tags, movetext) = pgn.split_once("\n\n").unwrap();
+    assert_eq!(
+        tags.lines().collect::<Vec<&str>>(),
+        vec![
+            "[Event \"?\"]",
+            "[Site \"?\"]",
+            "[Date \"????.??.??\"]",
+            "[Round \"?\"]",
+            "[White \"Quote \\\"\\\\ me\"]",
+            "[Black \"?\"]",
+            "[Result \"1/2-1/2\"]",
+            "[SetUp \"1\"]",
+            "[FEN \"4k3/8/8/8/8/8/4p3/4K3 b - - 0 40\"]",
+        ]
+    );
+    assert!(movetext.starts_with("40... Kd7 {a long comment"));
+    assert!(movetext.contains("} 41. Kxe2 1/2-1/2"));
+    assert!(movetext.lines().count() > 1);
+    assert!(movetext.lines().all(|line| line.chars().count() <= 80));
+
+    let reread = read_game(&pgn);
+    assert_eq!(reread.tag("White"), Some("Quote \"\\ me"));
+    assert_eq!(reread.starting_position, game.starting_position);
+    assert_eq!(reread.main_line, game.main_line);
+}
+
+#[test]
+fn chess960_games_keep_their_start_position() {
+    let mut game = PgnGame::new(chess960::start_position(0).unwrap());
+    let position = game.starting_position.clone();
+    game.main_line
+        .push(GameNode::new(position.parse_san("b4").unwrap()));
+
+    let pgn = game.to_string();
+    assert!(pgn.contains("[FEN \"bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1\"]"));
+    assert!(pgn.ends_with("\n1. b4 *\n"));
+    assert_eq!(read_game(&pgn).main_line, game.main_line);
+
+    // the initial position needs no FEN tag
+    assert!(!PgnGame::default().to_string().contains("FEN"));
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use jaingo_unchaind_rust::{
+    fen_parser::{parse_fen, INITIAL_POSITION},
+    move_generator::generate_all_moves,
+    position::Position,
+    san::SanError,
+};
+
+// plays the SAN moves and checks every one is written back the same way
+fn play(position: &mut Position, moves: &str) {
+    for san in moves.split_whitespace() {
+        let mv = position.parse_san(san).unwrap();
+        assert_eq!(position.to_san(mv), san);
+        position.make_move(mv);
+    }
+}
+
+#[test]
+fn pieces_pawns_and_captures() {
+    let mut position = parse_fen(INITIAL_POSITION).unwrap();
+    play(
+        &mut position,
+        "e4 d5 exd5 Qxd5 Nc3 Qa5 d4 Nf6 Nf3 Bf5 Bc4 e6 O-O c6 Bd2 Bb4 Re1",
+    );
+}
+
+#[test]
+fn disambiguation_by_file_rank_and_square() {
+    let rooks = parse_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+    assert_eq!(rooks.to_san(rooks.parse_san("Rad1").unwrap()), "Rad1");
+    assert_eq!(rooks.to_san(rooks.parse_san("Rhd1").unwrap()), "Rhd1");
+    assert_eq!(
+        rooks.parse_san("Rd1"),
+        Err(SanError::AmbiguousMove(String::from("Rd1")))
+    );
+
+    let rooks = parse_fen("4k3/8/8/R7/8/8/4K3/R7 w - - 0 1").unwrap();
+    assert_eq!(rooks.to_san(rooks.parse_san("R1a3").unwrap()), "R1a3");
+    assert_eq!(rooks.to_san(rooks.parse_san("R5a3").unwrap()), "R5a3");
+
+    let queens = parse_fen("4k3/8/8/8/8/Q7/4K3/Q1Q5 w - - 0 1").unwrap();
+    let mv = queens.parse_san("Qa1b2").unwrap();
+    assert_eq!(queens.to_san(mv), "Qa1b2");
+    // the shorter forms leave two queens to choose from
+    assert!(queens.parse_san("Qab2").is_err());
+    assert!(queens.parse_san("Q1b2").is_err());
+    assert_eq!(queens.to_san(queens.parse_san("Qa3b2").unwrap()), "Q3b2");
+}
+
+#[test]
+fn promotions_checks_and_mates() {
+    let mut promotion = parse_fen("3nk3/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        promotion.to_san(promotion.parse_san("exd8=Q+").unwrap()),
+        "exd8=Q+"
+    );
+    assert_eq!(
+        promotion.to_san(promotion.parse_san("exd8N").unwrap()),
+        "exd8=N"
+    );
+    assert_eq!(
+        promotion.parse_san("exd8"),
+        Err(SanError::IllegalMove(String::from("exd8")))
+    );
+    play(&mut promotion, "exd8=R+ Kxd8");
+
+    let mut fools_mate = parse_fen(INITIAL_POSITION).unwrap();
+    play(&mut fools_mate, "f3 e5 g4 Qh4#");
+}
+
+#[test]
+fn castling_notations() {
+    let castles = parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let king_side = castles.parse_san("O-O").unwrap();
+    assert!(king_side.is_castle());
+    assert_eq!(castles.parse_san("0-0"), Ok(king_side));
+    assert_eq!(castles.to_san(king_side), "O-O");
+    assert_eq!(castles.to_san(castles.parse_san("0-0-0").unwrap()), "O-O-O");
+    // a king move to the castling square is a different move
+    assert!(castles.parse_san("Kg1").is_err());
+
+    let chess960 = parse_fen("1r2k3/8/8/8/8/8/8/1R2K3 w Bb - 0 1").unwrap();
+    assert_eq!(
+        chess960.to_san(chess960.parse_san("O-O-O").unwrap()),
+        "O-O-O"
+    );
+}
+
+#[test]
+fn annotations_are_ignored() {
+    let position = parse_fen(INITIAL_POSITION).unwrap();
+    let knight = position.parse_san("Nf3").unwrap();
+    for san in ["Nf3!?", "Nf3!", "Nf3??", "Ng1f3", "Ngf3", " Nf3 "] {
+        assert_eq!(position.parse_san(san), Ok(knight), "{}", san);
+    }
+}
+
+#[test]
+fn malformed_and_illegal_moves() {
+    let position = parse_fen(INITIAL_POSITION).unwrap();
+    for san in ["", "Xe4", "e9", "N", "Nfgf3", "e8=K", "O-O-O-O"] {
+        assert_eq!(
+            position.parse_san(san),
+            Err(SanError::InvalidSyntax(String::from(san))),
+            "{}",
+            san
+        );
+    }
+    for san in ["e5", "Nf4", "O-O", "Ke2", "exd3"] {
+        assert_eq!(
+            position.parse_san(san),
+            Err(SanError::IllegalMove(String::from(san))),
+            "{}",
+            san
+        );
+    }
+}
+
+#[test]
+fn every_legal_move_round_trips() {
+    for position in common::sample_positions() {
+        for mv in generate_all_moves(&position) {
+            let san = position.to_san(mv);
+            assert_eq!(position.parse_san(&san), Ok(mv), "{}", san);
+        }
+    }
+}
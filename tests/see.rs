@@ -0,0 +1,79 @@
+use jaingo_unchaind_rust::{
+    fen_parser::parse_fen,
+    moves::{Move, Square, CAPTURE, EN_PASSANT_CAPTURE, QUIET_MOVE},
+    piece::PieceType,
+    see::PieceValues,
+};
+
+fn capture(from: &str, to: &str) -> Move {
+    return Move::new(&from.parse().unwrap(), &to.parse().unwrap(), CAPTURE);
+}
+
+#[test]
+fn see_undefended_and_defended_pawn() {
+    let position = parse_fen("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1").unwrap();
+    assert_eq!(position.see(capture("e1", "e5")), 100);
+
+    let position = parse_fen("1k1r4/1pp4p/p2p4/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1").unwrap();
+    assert_eq!(position.see(capture("e1", "e5")), -400);
+    assert!(position.see_ge(capture("e1", "e5"), -400));
+    assert!(!position.see_ge(capture("e1", "e5"), 0));
+}
+
+#[test]
+fn see_with_x_ray_attackers() {
+    // the rook on e2 is backed by the queen on e1, the bishop on f6 by the queen on h8
+    let position = parse_fen("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1").unwrap();
+    assert_eq!(position.see(capture("d3", "e5")), -220);
+
+    // doubled rooks win the defended pawn
+    let position = parse_fen("4k3/3r4/8/3p4/8/8/3R4/3RK3 w - - 0 1").unwrap();
+    assert_eq!(position.see(capture("d2", "d5")), 100);
+}
+
+#[test]
+fn see_king_does_not_capture_defended_piece() {
+    let position = parse_fen("8/8/4k3/3p4/8/3Q4/8/3RK3 w - - 0 1").unwrap();
+    assert_eq!(position.see(capture("d3", "d5")), 100);
+
+    let position = parse_fen("8/8/4k3/3p4/8/3Q4/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(position.see(capture("d3", "d5")), -800);
+}
+
+#[test]
+fn see_en_passant_promotion_and_quiet_moves() {
+    let position = parse_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+    let en_passant = Move::new(
+        &"e5".parse().unwrap(),
+        &"d6".parse().unwrap(),
+        EN_PASSANT_CAPTURE,
+    );
+    assert_eq!(position.see(en_passant), 100);
+
+    let position = parse_fen("3rk3/2P5/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let promotion = Move::promotion(&Square(50), &Square(58), &PieceType::Queen, false);
+    assert_eq!(position.see(promotion), -100);
+    let capture_promotion = Move::promotion(&Square(50), &Square(59), &PieceType::Queen, true);
+    assert_eq!(position.see(capture_promotion), 400);
+
+    // a quiet move to an attacked square loses the piece
+    let position = parse_fen("4k3/8/8/4p3/8/8/8/2B1K3 w - - 0 1").unwrap();
+    let quiet = Move::new(&"c1".parse().unwrap(), &"e3".parse().unwrap(), QUIET_MOVE);
+    assert_eq!(position.see(quiet), 0);
+    let into_pawn = Move::new(&"c1".parse().unwrap(), &"f4".parse().unwrap(), QUIET_MOVE);
+    assert_eq!(position.see(into_pawn), -330);
+}
+
+#[test]
+fn see_with_custom_values() {
+    let position = parse_fen("1k1r4/1pp4p/p2p4/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1").unwrap();
+    let piece_values = PieceValues {
+        rook: 450,
+        ..PieceValues::default()
+    };
+    assert_eq!(
+        position.see_with_values(capture("e1", "e5"), &piece_values),
+        -350
+    );
+    assert!(position.see_ge_with_values(capture("e1", "e5"), -350, &piece_values));
+}
@@ -0,0 +1,153 @@
+use jaingo_unchaind_rust::{
+    bitboard::Bitboard, magic, moves::Square, pext, piece::PieceType, position::Color,
+};
+
+static ROOK_DIRECTIONS: [(i8, i8); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+static BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+// walks every ray square by square until it leaves the board or hits a piece
+fn naive_attacks(square: u8, occupied_squares: Bitboard, directions: &[(i8, i8)]) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    for (file_step, rank_step) in directions {
+        let (mut file, mut rank) = ((square % 8) as i8, (square / 8) as i8);
+        loop {
+            file += file_step;
+            rank += rank_step;
+            if !(0..8).contains(&file) || !(0..8).contains(&rank) {
+                break;
+            }
+            let square_bb = Bitboard(1 << (rank * 8 + file));
+            attacks |= square_bb;
+            if !(occupied_squares & square_bb).is_empty() {
+                break;
+            }
+        }
+    }
+    return attacks;
+}
+
+// xorshift64, enough randomness for occupancies without pulling in a dependency
+struct Occupancies(u64);
+
+impl Occupancies {
+    fn next(&mut self) -> Bitboard {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        return Bitboard(self.0);
+    }
+}
+
+fn assert_slider_attacks(occupied_squares: Bitboard) {
+    for square in 0..64 {
+        let rook = naive_attacks(square, occupied_squares, &ROOK_DIRECTIONS);
+        let bishop = naive_attacks(square, occupied_squares, &BISHOP_DIRECTIONS);
+        // the split between our and opponent pieces must not matter
+        let (ours, theirs) = (
+            occupied_squares & Bitboard(0x5555555555555555),
+            occupied_squares & Bitboard(0xaaaaaaaaaaaaaaaa),
+        );
+
+        for (piece, expected) in [
+            (PieceType::Rook, rook),
+            (PieceType::Bishop, bishop),
+            (PieceType::Queen, rook | bishop),
+        ] {
+            assert_eq!(
+                piece.attact_bitboard(&Color::White, &Square(square), &ours, &theirs),
+                expected,
+                "{} on {} with occupancy {:#018x}",
+                piece,
+                Square(square),
+                occupied_squares.0
+            );
+        }
+    }
+}
+
+#[test]
+fn slider_attacks_on_empty_board() {
+    assert_slider_attacks(Bitboard::EMPTY);
+}
+
+#[test]
+fn slider_attacks_on_full_board() {
+    assert_slider_attacks(Bitboard::FULL);
+}
+
+#[test]
+fn slider_attacks_with_random_occupancies() {
+    let mut occupancies = Occupancies(0x9e3779b97f4a7c15);
+    for _ in 0..1000 {
+        // sparse, medium and dense boards
+        let (a, b, c) = (occupancies.next(), occupancies.next(), occupancies.next());
+        assert_slider_attacks(a & b & c);
+        assert_slider_attacks(a & b);
+        assert_slider_attacks(a | b);
+    }
+}
+
+// the magic tables are only wired into attact_bitboard with the magic feature,
+// check the generated constants regardless of the selected backend
+#[test]
+fn magic_attacks_with_random_occupancies() {
+    let mut occupancies = Occupancies(0x2545f4914f6cdd1d);
+    for _ in 0..1000 {
+        let occupied_squares = occupancies.next() & occupancies.next();
+        for square in 0..64 {
+            assert_eq!(
+                magic::rook_attacks(&Square(square), &occupied_squares),
+                naive_attacks(square, occupied_squares, &ROOK_DIRECTIONS)
+            );
+            assert_eq!(
+                magic::bishop_attacks(&Square(square), &occupied_squares),
+                naive_attacks(square, occupied_squares, &BISHOP_DIRECTIONS)
+            );
+        }
+    }
+}
+
+#[test]
+fn pext_attacks_match_other_backends() {
+    if !pext::is_available() {
+        eprintln!("BMI2 not supported by this CPU, skipping the PEXT cross check");
+        return;
+    }
+
+    let mut occupancies = Occupancies(0x853c49e6748fea9b);
+    for _ in 0..1000 {
+        let occupied_squares = occupancies.next() & occupancies.next();
+        for square in 0..64 {
+            let rook = pext::rook_attacks(&Square(square), &occupied_squares);
+            let bishop = pext::bishop_attacks(&Square(square), &occupied_squares);
+
+            assert_eq!(
+                rook,
+                magic::rook_attacks(&Square(square), &occupied_squares)
+            );
+            assert_eq!(
+                bishop,
+                magic::bishop_attacks(&Square(square), &occupied_squares)
+            );
+            // attact_bitboard runs whichever backend the enabled features select
+            assert_eq!(
+                rook,
+                PieceType::Rook.attact_bitboard(
+                    &Color::White,
+                    &Square(square),
+                    &occupied_squares,
+                    &Bitboard::EMPTY
+                )
+            );
+            assert_eq!(
+                bishop,
+                PieceType::Bishop.attact_bitboard(
+                    &Color::White,
+                    &Square(square),
+                    &occupied_squares,
+                    &Bitboard::EMPTY
+                )
+            );
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use jaingo_unchaind_rust::{
+    fen_parser::parse_fen,
+    move_generator::{generate_all_moves, generate_moves, GenerationMode},
+    move_picker::MovePicker,
+    moves::Move,
+    position::Position,
+};
+
+static FENS: [&str; 5] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "4k3/8/8/8/8/5n2/8/4K2r w - - 0 1",
+];
+
+fn move_set(moves: Vec<Move>) -> HashSet<Move> {
+    let move_count = moves.len();
+    let moves: HashSet<Move> = moves.into_iter().collect();
+    assert_eq!(moves.len(), move_count, "duplicate moves generated");
+    return moves;
+}
+
+fn assert_generation_modes(position: &mut Position) {
+    let all = move_set(generate_all_moves(position));
+    let captures = move_set(generate_moves(position, GenerationMode::Captures));
+    let quiets = move_set(generate_moves(position, GenerationMode::Quiets));
+    let evasions = move_set(generate_moves(position, GenerationMode::Evasions));
+    let quiet_checks = move_set(generate_moves(position, GenerationMode::QuietChecks));
+
+    assert!(captures.is_disjoint(&quiets));
+    assert_eq!(&captures | &quiets, all);
+    assert!(captures
+        .iter()
+        .all(|mv| mv.is_capture() || mv.is_promotion()));
+
+    if !position.checkers().is_empty() {
+        assert_eq!(evasions, all);
+    } else {
+        assert!(evasions.is_empty());
+    }
+
+    let expected_quiet_checks: HashSet<Move> = quiets
+        .iter()
+        .copied()
+        .filter(|mv| {
+            let undo = position.make_move(*mv);
+            let gives_check = !position.checkers().is_empty();
+            position.unmake_move(*mv, undo);
+            gives_check
+        })
+        .collect();
+    assert_eq!(quiet_checks, expected_quiet_checks);
+}
+
+#[test]
+fn generation_modes_partition_legal_moves() {
+    for fen in FENS {
+        let mut position = parse_fen(fen).unwrap();
+        assert_generation_modes(&mut position);
+
+        for mv in generate_all_moves(&position.clone()) {
+            let undo = position.make_move(mv);
+            assert_generation_modes(&mut position);
+            position.unmake_move(mv, undo);
+        }
+    }
+}
+
+#[test]
+fn move_picker_yields_every_legal_move_once() {
+    for fen in FENS {
+        let position = parse_fen(fen).unwrap();
+        let all = move_set(generate_all_moves(&position));
+        let picked: Vec<Move> = MovePicker::new(&position, None, &[]).collect();
+
+        assert_eq!(move_set(picked), all);
+    }
+}
+
+#[test]
+fn move_picker_order() {
+    let position = parse_fen(FENS[0]).unwrap();
+    let legal_move = |uci: &str| {
+        generate_all_moves(&position)
+            .into_iter()
+            .find(|mv| mv.to_string() == uci)
+            .unwrap()
+    };
+    let (hash_move, killer) = (legal_move("e2a6"), legal_move("a2a3"));
+    // not legal here, must be skipped
+    let illegal_killer: Move = "a1a8".parse().unwrap();
+
+    let picked: Vec<Move> =
+        MovePicker::new(&position, Some(hash_move), &[illegal_killer, killer]).collect();
+    assert_eq!(picked.len(), 48);
+    assert_eq!(picked[0], hash_move);
+
+    let killer_index = picked.iter().position(|mv| *mv == killer).unwrap();
+    // good captures come before the killer, quiet moves after it
+    assert!(picked[1..killer_index]
+        .iter()
+        .all(|mv| mv.is_capture() && position.see_ge(*mv, 0)));
+    assert!(picked[killer_index + 1..]
+        .iter()
+        .take_while(|mv| !mv.is_capture())
+        .all(|mv| !mv.is_promotion()));
+    // losing captures come last
+    let first_bad_capture = picked
+        .iter()
+        .position(|mv| mv.is_capture() && !position.see_ge(*mv, 0))
+        .unwrap();
+    assert!(picked[first_bad_capture..]
+        .iter()
+        .all(|mv| mv.is_capture() && !position.see_ge(*mv, 0)));
+
+    // an illegal hash move is dropped
+    let picked: Vec<Move> = MovePicker::new(&position, Some(illegal_killer), &[]).collect();
+    assert_eq!(picked.len(), 48);
+    assert!(!picked.contains(&illegal_killer));
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use jaingo_unchaind_rust::{
+    fen_parser::{parse_fen, INITIAL_POSITION},
+    move_generator::generate_all_moves,
+    moves::{CAPTURE, DOUBLE_PAWN_PUSH, EN_PASSANT_CAPTURE, KING_CASTLE, QUEEN_CASTLE, QUIET_MOVE},
+    piece::PieceType,
+    uci::UciMoveError,
+};
+
+#[test]
+fn flags_come_from_the_board() {
+    let initial = parse_fen(INITIAL_POSITION).unwrap();
+    assert_eq!(
+        initial.parse_uci_move("e2e4").unwrap().flags(),
+        DOUBLE_PAWN_PUSH
+    );
+    assert_eq!(initial.parse_uci_move("e2e3").unwrap().flags(), QUIET_MOVE);
+    assert_eq!(initial.parse_uci_move("g1f3").unwrap().flags(), QUIET_MOVE);
+
+    let en_passant = parse_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    assert_eq!(
+        en_passant.parse_uci_move("e5d6").unwrap().flags(),
+        EN_PASSANT_CAPTURE
+    );
+
+    let promotion = parse_fen("3nk3/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let mv = promotion.parse_uci_move("e7d8n").unwrap();
+    assert!(mv.is_capture());
+    assert_eq!(mv.promotion_piece(), Some(PieceType::Knight));
+    assert_eq!(promotion.to_uci(mv, false), "e7d8n");
+    assert_eq!(
+        promotion.parse_uci_move("e7e8").unwrap_err(),
+        UciMoveError::IllegalMove(String::from("e7e8"))
+    );
+
+    let capture = parse_fen("4k3/8/8/8/8/8/4K3/R6r w - - 0 1").unwrap();
+    assert_eq!(capture.parse_uci_move("a1h1").unwrap().flags(), CAPTURE);
+}
+
+#[test]
+fn castling_in_both_forms() {
+    let castles = parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let king_side = castles.parse_uci_move("e1g1").unwrap();
+    assert_eq!(king_side.flags(), KING_CASTLE);
+    assert_eq!(castles.parse_uci_move("e1h1"), Ok(king_side));
+    assert_eq!(castles.to_uci(king_side, false), "e1g1");
+    assert_eq!(castles.to_uci(king_side, true), "e1h1");
+
+    let queen_side = castles.parse_uci_move("e1a1").unwrap();
+    assert_eq!(queen_side.flags(), QUEEN_CASTLE);
+    assert_eq!(castles.to_uci(queen_side, false), "e1c1");
+
+    let no_rights = parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+    assert!(no_rights.parse_uci_move("e1g1").is_err());
+    assert!(no_rights.parse_uci_move("e1h1").is_err());
+}
+
+#[test]
+fn chess960_king_next_to_its_target_square() {
+    // the king can castle to c1 or simply step there
+    let position = parse_fen("4k3/8/8/8/8/8/8/RK6 w A - 0 1").unwrap();
+    let step = position.parse_uci_move("b1c1").unwrap();
+    assert!(!step.is_castle());
+    let castle = position.parse_uci_move("b1a1").unwrap();
+    assert_eq!(castle.flags(), QUEEN_CASTLE);
+    assert_eq!(position.to_uci(castle, true), "b1a1");
+
+    // a king already on its target square
+    let position = parse_fen("4k3/8/8/8/8/8/8/6KR w H - 0 1").unwrap();
+    let castle = position.parse_uci_move("g1h1").unwrap();
+    assert_eq!(castle.flags(), KING_CASTLE);
+    assert_eq!(position.to_uci(castle, true), "g1h1");
+}
+
+#[test]
+fn malformed_and_illegal_moves() {
+    let initial = parse_fen(INITIAL_POSITION).unwrap();
+    for uci in ["", "e2e", "e2e4e", "e7e8k", "i2i4", "0000"] {
+        assert!(initial.parse_uci_move(uci).is_err(), "{}", uci);
+    }
+    assert_eq!(
+        initial.parse_uci_move("e2e5"),
+        Err(UciMoveError::IllegalMove(String::from("e2e5")))
+    );
+    assert_eq!(
+        initial.parse_uci_move("e2e"),
+        Err(UciMoveError::InvalidSyntax(String::from("e2e")))
+    );
+}
+
+#[test]
+fn every_legal_move_round_trips() {
+    for position in common::sample_positions() {
+        for mv in generate_all_moves(&position) {
+            assert_eq!(position.parse_uci_move(&position.to_uci(mv, false)), Ok(mv));
+            assert_eq!(position.parse_uci_move(&position.to_uci(mv, true)), Ok(mv));
+        }
+    }
+}
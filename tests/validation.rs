@@ -0,0 +1,102 @@
+use jaingo_unchaind_rust::{
+    fen_parser::{parse_fen, parse_fen_strict, FenError},
+    moves::Square,
+    perft::parse_perft_suite,
+    position::Color,
+    validation::PositionError,
+};
+
+fn validate(fen: &str) -> Result<(), PositionError> {
+    return parse_fen(fen).unwrap().validate();
+}
+
+#[test]
+fn perft_suite_positions_are_valid() {
+    for entry in parse_perft_suite(include_str!("data/perftsuite.epd")).unwrap() {
+        assert_eq!(entry.position.validate(), Ok(()));
+    }
+    assert!(validate("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").is_ok());
+}
+
+#[test]
+fn ranks_must_have_8_files() {
+    assert!(parse_fen("rnbqkbnrr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").is_err());
+    assert!(parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN1R w KQkq - 0 1").is_err());
+    assert!(parse_fen("rnbqkbnr/pppppppp/8/8/8/7/PPPPPPPP/RNBQKBNR w KQkq - 0 1").is_err());
+    assert!(parse_fen("4k3/8/8/8/8/8/8/4K2R1 w - - 0 1").is_err());
+}
+
+#[test]
+fn kings_and_pawns() {
+    assert_eq!(
+        validate("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(PositionError::MissingKing(Color::Black))
+    );
+    assert_eq!(
+        validate("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+        Err(PositionError::TooManyKings(Color::White))
+    );
+    assert_eq!(
+        validate("4k2P/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(PositionError::PawnOnBackRank(Square(63)))
+    );
+    assert_eq!(
+        validate("4k3/8/8/8/8/8/8/p3K3 b - - 0 1"),
+        Err(PositionError::PawnOnBackRank(Square(0)))
+    );
+}
+
+#[test]
+fn side_not_to_move_in_check() {
+    assert_eq!(
+        validate("4k3/8/8/8/8/8/8/4K2r b - - 0 1"),
+        Err(PositionError::OpponentInCheck)
+    );
+    assert!(validate("4k3/8/8/8/8/8/8/4K2r w - - 0 1").is_ok());
+}
+
+#[test]
+fn castling_rights_need_king_and_rook_at_home() {
+    assert_eq!(
+        validate("4k3/8/8/8/8/8/8/4K3 w K - 0 1"),
+        Err(PositionError::InvalidCastlingRight(Color::White, true))
+    );
+    assert_eq!(
+        validate("r3k3/8/8/8/8/8/4K3/7R w Kq - 0 1"),
+        Err(PositionError::InvalidCastlingRight(Color::White, true))
+    );
+    assert_eq!(
+        validate("4kr2/8/8/8/8/8/8/4K3 w q - 0 1"),
+        Err(PositionError::InvalidCastlingRight(Color::Black, false))
+    );
+    assert!(validate("r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1").is_ok());
+}
+
+#[test]
+fn en_passant_target_behind_a_pawn() {
+    assert_eq!(
+        validate("4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1"),
+        Err(PositionError::InvalidEnPassantTarget(Square(20)))
+    );
+    assert_eq!(
+        validate("4k3/8/8/8/8/8/8/4K3 b - e3 0 1"),
+        Err(PositionError::InvalidEnPassantTarget(Square(20)))
+    );
+    assert_eq!(
+        validate("4k3/8/8/8/4P3/8/8/4K3 b - e4 0 1"),
+        Err(PositionError::InvalidEnPassantTarget(Square(28)))
+    );
+}
+
+#[test]
+fn strict_parsing_validates() {
+    let fen = "4k3/8/8/8/8/8/8/4K3 w K - 0 1";
+    assert!(parse_fen(fen).is_ok());
+    assert_eq!(
+        parse_fen_strict(fen),
+        Err(FenError::InvalidPosition(
+            PositionError::InvalidCastlingRight(Color::White, true)
+        ))
+    );
+    assert!(parse_fen_strict("4k3/8/8/8/8/8/8/4K2R w K - 0 1").is_ok());
+}
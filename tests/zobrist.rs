@@ -0,0 +1,55 @@
+use jaingo_unchaind_rust::{
+    fen_parser::parse_fen, move_generator::generate_all_moves, moves::Move, position::Position,
+};
+
+fn play(position: &mut Position, moves: &[&str]) {
+    for uci in moves {
+        let mv: Move = generate_all_moves(position)
+            .into_iter()
+            .find(|mv| mv.to_string() == *uci)
+            .unwrap();
+        position.make_move(mv);
+        assert_eq!(position.hash, position.compute_hash());
+    }
+}
+
+#[test]
+fn transpositions_share_the_hash() {
+    let initial = parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+
+    let mut position = initial.clone();
+    play(&mut position, &["g1f3", "g8f6", "f3g1", "f6g8"]);
+    assert_eq!(position.hash, initial.hash);
+
+    let (mut first, mut second) = (initial.clone(), initial.clone());
+    play(&mut first, &["e2e3", "e7e6", "d2d3"]);
+    play(&mut second, &["d2d3", "e7e6", "e2e3"]);
+    assert_eq!(first.hash, second.hash);
+}
+
+#[test]
+fn hash_covers_side_castling_and_en_passant() {
+    let hash = |fen: &str| parse_fen(fen).unwrap().hash;
+
+    let base = hash("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1");
+    assert_ne!(base, hash("r3k2r/8/8/3pP3/8/8/8/R3K2R b KQkq d6 0 1"));
+    assert_ne!(base, hash("r3k2r/8/8/3pP3/8/8/8/R3K2R w Qkq d6 0 1"));
+    assert_ne!(base, hash("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq - 0 1"));
+    // the clocks are not part of the key
+    assert_eq!(base, hash("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 7 30"));
+}
+
+#[test]
+fn unmake_move_restores_the_hash() {
+    let mut position =
+        parse_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+    let hash = position.hash;
+
+    for mv in generate_all_moves(&position.clone()) {
+        let undo = position.make_move(mv);
+        assert_ne!(position.hash, hash);
+        assert_eq!(position.hash, position.compute_hash());
+        position.unmake_move(mv, undo);
+        assert_eq!(position.hash, hash);
+    }
+}